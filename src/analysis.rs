@@ -0,0 +1,176 @@
+//! Measures of how evenly particles are spread out. Everything here works on plain positions in a toroidal space of the given dimensions, so it does not depend on any running systems.
+
+use bevy::prelude::*;
+
+use crate::common::wrapping_offset_2d;
+
+/// Summary of the distances from every particle to its nearest neighbour.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NearestNeighbourSummary {
+	pub mean: f32,
+	pub standard_deviation: f32,
+	/// The Clark-Evans ratio: the mean nearest neighbour distance divided by the one expected from randomly placed particles at the same density. Around 1 means random, lower means clustered and higher means regular, up to about 2.15 for a perfect hexagonal lattice.
+	pub clark_evans: f32,
+}
+
+/// The distance from every position to the nearest other position, in the same order as the positions.
+pub fn nearest_neighbour_distances(positions: &[Vec2], dimensions: Vec2) -> Vec<f32> {
+	positions
+		.iter()
+		.enumerate()
+		.map(|(index, &position)| {
+			positions
+				.iter()
+				.enumerate()
+				.filter(|(other_index, _)| *other_index != index)
+				.map(|(_, &other)| wrapping_offset_2d(position, other, dimensions).length_squared())
+				.fold(f32::INFINITY, f32::min)
+				.sqrt()
+		})
+		.collect()
+}
+
+/// Returns `None` when there are fewer than 2 positions, since there are no neighbours then.
+pub fn nearest_neighbour_summary(
+	positions: &[Vec2],
+	dimensions: Vec2,
+) -> Option<NearestNeighbourSummary> {
+	if positions.len() < 2 {
+		return None;
+	}
+	let distances = nearest_neighbour_distances(positions, dimensions);
+	let count = distances.len() as f32;
+	let mean = distances.iter().sum::<f32>() / count;
+	let variance = distances
+		.iter()
+		.map(|distance| (distance - mean).powi(2))
+		.sum::<f32>()
+		/ count;
+	let density = count / (dimensions.x * dimensions.y);
+	let expected_mean = 0.5 / density.sqrt();
+
+	Some(NearestNeighbourSummary {
+		mean,
+		standard_deviation: variance.sqrt(),
+		clark_evans: mean / expected_mean,
+	})
+}
+
+/// The coefficient of variation of the particle counts in a grid of roughly square cells covering the space, with `cells` cells along the shortest dimension. 0 means every cell holds exactly as many particles.
+pub fn density_variation(positions: &[Vec2], dimensions: Vec2, cells: u32) -> f32 {
+	if positions.is_empty() {
+		return 0.0;
+	}
+	let cell_size = dimensions.min_element() / cells as f32;
+	let columns = (dimensions.x / cell_size).round().max(1.0) as usize;
+	let rows = (dimensions.y / cell_size).round().max(1.0) as usize;
+	let mut counts = vec![0u32; columns * rows];
+	for position in positions {
+		let column = ((position.x / dimensions.x * columns as f32) as usize).min(columns - 1);
+		let row = ((position.y / dimensions.y * rows as f32) as usize).min(rows - 1);
+		counts[row * columns + column] += 1;
+	}
+	let mean = positions.len() as f32 / counts.len() as f32;
+	let variance = counts
+		.iter()
+		.map(|&count| (count as f32 - mean).powi(2))
+		.sum::<f32>()
+		/ counts.len() as f32;
+	variance.sqrt() / mean
+}
+
+/// The radial distribution function g(r), sampled in `bins` equally wide bins from 0 to `max_radius`. Each value is the density of particles at that distance from an average particle, relative to the overall density. 1 everywhere means no structure; evenly spread particles show a dip near 0 and a peak at their typical spacing.
+///
+/// `max_radius` is limited to half the shortest dimension, beyond which distances under wrapping are no longer unique.
+pub fn radial_distribution(
+	positions: &[Vec2],
+	dimensions: Vec2,
+	max_radius: f32,
+	bins: usize,
+) -> Vec<f32> {
+	let mut histogram = vec![0u32; bins];
+	if positions.len() < 2 || bins == 0 {
+		return vec![0.0; bins];
+	}
+	let max_radius = max_radius.min(dimensions.min_element() / 2.0);
+	let bin_width = max_radius / bins as f32;
+
+	for (index, &position) in positions.iter().enumerate() {
+		for &other in &positions[index + 1..] {
+			let distance = wrapping_offset_2d(position, other, dimensions).length();
+			if distance < max_radius {
+				histogram[((distance / bin_width) as usize).min(bins - 1)] += 2;
+			}
+		}
+	}
+
+	let count = positions.len() as f32;
+	let density = count / (dimensions.x * dimensions.y);
+	histogram
+		.into_iter()
+		.enumerate()
+		.map(|(bin, pairs)| {
+			let inner = bin as f32 * bin_width;
+			let outer = inner + bin_width;
+			let ring_area = std::f32::consts::PI * (outer.powi(2) - inner.powi(2));
+			pairs as f32 / (count * density * ring_area)
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A square lattice with the given spacing, filling the dimensions exactly.
+	fn lattice(dimensions: Vec2, spacing: f32) -> Vec<Vec2> {
+		let columns = (dimensions.x / spacing) as u32;
+		let rows = (dimensions.y / spacing) as u32;
+		(0..rows)
+			.flat_map(|row| {
+				(0..columns)
+					.map(move |column| Vec2::new(column as f32 + 0.5, row as f32 + 0.5) * spacing)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn nearest_neighbours_wrap() {
+		let dimensions = Vec2::new(100.0, 100.0);
+		let positions = [Vec2::new(1.0, 50.0), Vec2::new(97.0, 50.0)];
+		let distances = nearest_neighbour_distances(&positions, dimensions);
+		assert!(
+			distances
+				.iter()
+				.all(|distance| (distance - 4.0).abs() < 0.001)
+		);
+	}
+
+	#[test]
+	fn lattice_is_regular() {
+		let dimensions = Vec2::new(200.0, 100.0);
+		let positions = lattice(dimensions, 10.0);
+		let summary = nearest_neighbour_summary(&positions, dimensions).unwrap();
+		assert!((summary.mean - 10.0).abs() < 0.001);
+		assert!(summary.standard_deviation < 0.001);
+		assert!((summary.clark_evans - 2.0).abs() < 0.001);
+		assert!(density_variation(&positions, dimensions, 5) < 0.001);
+	}
+
+	#[test]
+	fn clustered_density_varies() {
+		let dimensions = Vec2::new(100.0, 100.0);
+		let positions = vec![Vec2::new(10.0, 10.0); 50];
+		assert!(density_variation(&positions, dimensions, 4) > 1.0);
+	}
+
+	#[test]
+	fn lattice_radial_distribution() {
+		let dimensions = Vec2::new(100.0, 100.0);
+		let positions = lattice(dimensions, 10.0);
+		let distribution = radial_distribution(&positions, dimensions, 20.0, 20);
+		// Nothing is closer than the lattice spacing, and the 4 direct neighbours are exactly at it.
+		assert!(distribution[..10].iter().all(|&value| value == 0.0));
+		assert!(distribution[10] > 1.0);
+	}
+}
@@ -0,0 +1,83 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+
+const USAGE: &str = "Usage: particles [--metrics <path>] [--metrics-interval <ticks>] [--trajectories <path>] [--grid <size>] [--emitter-budget <count>] [--emitter-lifetime <seconds>]
+
+  --metrics <path>            Record metrics from launch. A .json or .jsonl extension writes JSON lines, anything else CSV.
+  --metrics-interval <ticks>  Record metrics every this many ticks. Defaults to 10.
+  --trajectories <path>       Record every particle's position and movement every tick from launch, as CSV.
+  --grid <size>               Spacing of the grid that dragged gizmos snap to while holding Ctrl. Defaults to 20.
+  --emitter-budget <count>    Each emitter placed pauses while this many of the particles it emitted are alive.
+  --emitter-lifetime <seconds>
+                              Particles from emitters placed fade out after this long.";
+
+/// Options given on the command line.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct CommandLine {
+	pub metrics_path: Option<PathBuf>,
+	pub metrics_interval: Option<u32>,
+	pub trajectories_path: Option<PathBuf>,
+	pub grid_size: Option<f32>,
+	pub emitter_budget: Option<u32>,
+	pub emitter_lifetime: Option<f32>,
+}
+
+impl CommandLine {
+	/// Parses the arguments, not including the program name. Errors contain a message to show the user.
+	pub fn parse(mut arguments: impl Iterator<Item = String>) -> Result<Self, String> {
+		let mut command_line = Self::default();
+		while let Some(argument) = arguments.next() {
+			let mut value = || {
+				arguments
+					.next()
+					.ok_or_else(|| format!("Missing value for {argument}\n\n{USAGE}"))
+			};
+			match argument.as_str() {
+				"--metrics" => command_line.metrics_path = Some(PathBuf::from(value()?)),
+				"--trajectories" => {
+					command_line.trajectories_path = Some(PathBuf::from(value()?));
+				}
+				"--metrics-interval" => {
+					let value = value()?;
+					let interval = value
+						.parse::<u32>()
+						.ok()
+						.filter(|interval| *interval > 0)
+						.ok_or_else(|| format!("Invalid interval {value}\n\n{USAGE}"))?;
+					command_line.metrics_interval = Some(interval);
+				}
+				"--grid" => {
+					let value = value()?;
+					let size = value
+						.parse::<f32>()
+						.ok()
+						.filter(|size| *size > 0.0)
+						.ok_or_else(|| format!("Invalid grid size {value}\n\n{USAGE}"))?;
+					command_line.grid_size = Some(size);
+				}
+				"--emitter-budget" => {
+					let value = value()?;
+					let budget = value
+						.parse::<u32>()
+						.ok()
+						.filter(|budget| *budget > 0)
+						.ok_or_else(|| format!("Invalid emitter budget {value}\n\n{USAGE}"))?;
+					command_line.emitter_budget = Some(budget);
+				}
+				"--emitter-lifetime" => {
+					let value = value()?;
+					let lifetime = value
+						.parse::<f32>()
+						.ok()
+						.filter(|lifetime| *lifetime > 0.0)
+						.ok_or_else(|| format!("Invalid emitter lifetime {value}\n\n{USAGE}"))?;
+					command_line.emitter_lifetime = Some(lifetime);
+				}
+				"--help" | "-h" => return Err(String::from(USAGE)),
+				_ => return Err(format!("Unknown argument {argument}\n\n{USAGE}")),
+			}
+		}
+		Ok(command_line)
+	}
+}
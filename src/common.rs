@@ -1,6 +1,26 @@
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*, window::PrimaryWindow};
 
-use crate::CLICK_RADIUS;
+use crate::{CLICK_RADIUS, WindowDimensions};
+
+/// Where the cursor is in the simulation, whose origin is in the bottom left rather than the top left like the window's.
+#[derive(SystemParam)]
+pub struct Cursor<'w, 's> {
+	window: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+	window_dimensions: Res<'w, WindowDimensions>,
+}
+
+impl Cursor<'_, '_> {
+	/// `None` while the cursor is outside the window.
+	pub fn position(&self) -> Option<Vec2> {
+		let window = self.window.single().ok()?;
+		let position = window.cursor_position()?;
+		Some(Vec2::new(position.x, window.height() - position.y))
+	}
+	/// The dimensions positions wrap around at.
+	pub fn window_dimensions(&self) -> Vec2 {
+		self.window_dimensions.get()
+	}
+}
 
 pub fn offset_2d(first: Vec2, second: Vec2, wrap: Option<Vec2>) -> Vec2 {
 	if let Some(wrap) = wrap {
@@ -30,6 +50,20 @@ fn wrapping_offset(first: f32, second: f32, wrap: f32) -> f32 {
 	}
 }
 
+/// Whether the line segment from `a_start` to `a_end` crosses the one from `b_start` to `b_end`. Touching counts as crossing.
+pub fn segments_intersect(a_start: Vec2, a_end: Vec2, b_start: Vec2, b_end: Vec2) -> bool {
+	let a = a_end - a_start;
+	let b = b_end - b_start;
+	let denominator = a.perp_dot(b);
+	if denominator == 0.0 {
+		return false;
+	}
+	let offset = b_start - a_start;
+	let along_a = offset.perp_dot(b) / denominator;
+	let along_b = offset.perp_dot(a) / denominator;
+	(0.0..=1.0).contains(&along_a) && (0.0..=1.0).contains(&along_b)
+}
+
 /// Find the entity closest to cursor within the click radius
 pub fn find_entity_by_cursor<'a>(
 	cursor_pos: Vec2,
@@ -0,0 +1,144 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+	input::Action,
+	particle::{Age, Particle, age_particles},
+};
+
+pub struct DecayPlugin;
+
+impl Plugin for DecayPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<Decay>()
+			.add_systems(Update, (adjust_decay, restore_decayed_particles).chain())
+			.add_systems(FixedUpdate, decay_particles.after(age_particles));
+	}
+}
+
+/// How old a particle is when it starts decaying.
+const DECAY_AGE: f32 = 20.0;
+/// How long it takes a particle to lose all of its charge once it starts decaying.
+const CHARGE_LOSS_DURATION: f32 = 5.0;
+const MIN_DECAY_RATE: f32 = 0.125;
+const MAX_DECAY_RATE: f32 = 16.0;
+
+/// How particles decay as they age, which applies to every particle.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct Decay {
+	pub mode: DecayMode,
+	/// How many seconds particles age per second.
+	pub rate: f32,
+}
+
+impl Decay {
+	/// The age at which particles are despawned, if decay despawns them.
+	pub fn lifetime(&self) -> Option<f32> {
+		(self.mode == DecayMode::Despawn).then_some(DECAY_AGE)
+	}
+}
+
+impl Default for Decay {
+	fn default() -> Self {
+		Self {
+			mode: DecayMode::Off,
+			rate: 1.0,
+		}
+	}
+}
+
+/// What happens to a particle once it is old enough.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecayMode {
+	/// Particles don't decay, and ones that had get their full charge back.
+	Off,
+	/// It is despawned, like a particle whose [`Lifetime`](crate::particle::Lifetime) is up.
+	Despawn,
+	/// Its charge weakens until it becomes [`Inert`].
+	LoseCharge,
+	/// It becomes [`Inert`] straight away.
+	Inert,
+}
+
+impl DecayMode {
+	fn next(self) -> Self {
+		match self {
+			Self::Off => Self::Despawn,
+			Self::Despawn => Self::LoseCharge,
+			Self::LoseCharge => Self::Inert,
+			Self::Inert => Self::Off,
+		}
+	}
+}
+
+/// The fraction of its charge a decaying particle has left, scaling the force between it and other particles.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Charge(pub f32);
+
+/// A particle that no longer exerts or feels the force between particles, and doesn't cancel out.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct Inert;
+
+fn adjust_decay(mut decay: ResMut<Decay>, action_state: Query<&ActionState<Action>>) {
+	let action_state = action_state.single().unwrap();
+	if action_state.just_pressed(&Action::CycleDecayMode) {
+		decay.mode = decay.mode.next();
+	}
+	if action_state.just_pressed(&Action::RaiseDecayRate) {
+		decay.rate = (decay.rate * 2.0).min(MAX_DECAY_RATE);
+	}
+	if action_state.just_pressed(&Action::LowerDecayRate) {
+		decay.rate = (decay.rate / 2.0).max(MIN_DECAY_RATE);
+	}
+}
+
+/// Switching decay off gives decayed particles their full charge back.
+fn restore_decayed_particles(
+	mut commands: Commands,
+	decay: Res<Decay>,
+	decayed: Query<Entity, (With<Particle>, Or<(With<Charge>, With<Inert>)>)>,
+) {
+	if !decay.is_changed() || decay.mode != DecayMode::Off {
+		return;
+	}
+	for entity in &decayed {
+		commands.entity(entity).try_remove::<(Charge, Inert)>();
+	}
+}
+
+/// Takes away the charge of particles that are old enough. Despawning them is left to [`age_particles`], along with particles whose lifetime is up.
+fn decay_particles(
+	mut commands: Commands,
+	decay: Res<Decay>,
+	mut particles: Query<(Entity, &Age, Option<&mut Charge>), (With<Particle>, Without<Inert>)>,
+) {
+	for (entity, age, charge) in &mut particles {
+		let overdue = age.0 - DECAY_AGE;
+		if overdue < 0.0 {
+			continue;
+		}
+		// It may have been despawned this tick, by cancelling out or reaching its lifetime.
+		match decay.mode {
+			DecayMode::Off | DecayMode::Despawn => {}
+			DecayMode::LoseCharge => {
+				let remaining = 1.0 - overdue / CHARGE_LOSS_DURATION;
+				if remaining <= 0.0 {
+					commands
+						.entity(entity)
+						.try_remove::<Charge>()
+						.try_insert(Inert);
+				} else if let Some(mut charge) = charge {
+					charge.0 = remaining;
+				} else {
+					commands.entity(entity).try_insert(Charge(remaining));
+				}
+			}
+			DecayMode::Inert => {
+				commands
+					.entity(entity)
+					.try_remove::<Charge>()
+					.try_insert(Inert);
+			}
+		}
+	}
+}
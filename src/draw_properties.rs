@@ -23,6 +23,14 @@ pub const NEGATIVE_PARTICLE: DrawProperties = DrawProperties {
 	texture: Some(crate::assets::Texture::Particle),
 };
 
+pub const fn particle(positive: bool) -> DrawProperties {
+	if positive {
+		POSITIVE_PARTICLE
+	} else {
+		NEGATIVE_PARTICLE
+	}
+}
+
 pub const POSITIVE_EMITTER: DrawProperties = DrawProperties {
 	draw_priority: 1.0,
 	size: 15.0,
@@ -37,6 +45,48 @@ pub const NEGATIVE_EMITTER: DrawProperties = DrawProperties {
 	texture: None,
 };
 
+pub const POSITIVE_DIRECTIONAL_EMITTER: DrawProperties = DrawProperties {
+	draw_priority: 1.0,
+	size: 0.7,
+	color: Color::srgb(1.0, 0.0, 0.0),
+	texture: Some(crate::assets::Texture::Pusher),
+};
+
+pub const NEGATIVE_DIRECTIONAL_EMITTER: DrawProperties = DrawProperties {
+	draw_priority: 1.0,
+	size: 0.7,
+	color: Color::srgb(0.0, 0.0, 1.0),
+	texture: Some(crate::assets::Texture::Pusher),
+};
+
+pub const POSITIVE_BURST_EMITTER: DrawProperties = DrawProperties {
+	draw_priority: 1.0,
+	size: 20.0,
+	color: Color::srgb(0.8, 0.0, 0.2),
+	texture: None,
+};
+
+pub const NEGATIVE_BURST_EMITTER: DrawProperties = DrawProperties {
+	draw_priority: 1.0,
+	size: 20.0,
+	color: Color::srgb(0.2, 0.0, 0.8),
+	texture: None,
+};
+
+pub const POSITIVE_AREA_EMITTER: DrawProperties = DrawProperties {
+	draw_priority: 0.9,
+	size: 25.0,
+	color: Color::srgb(0.6, 0.1, 0.1),
+	texture: None,
+};
+
+pub const NEGATIVE_AREA_EMITTER: DrawProperties = DrawProperties {
+	draw_priority: 0.9,
+	size: 25.0,
+	color: Color::srgb(0.1, 0.1, 0.6),
+	texture: None,
+};
+
 pub const DELETER: DrawProperties = DrawProperties {
 	draw_priority: 2.0,
 	size: 1.0,
@@ -51,6 +101,34 @@ pub const SLOW_DELETER: DrawProperties = DrawProperties {
 	texture: Some(crate::assets::Texture::Deleter),
 };
 
+pub const POSITIVE_SELECTIVE_DELETER: DrawProperties = DrawProperties {
+	draw_priority: 2.1,
+	size: 1.0,
+	color: Color::srgb(1.0, 0.6, 0.6),
+	texture: Some(crate::assets::Texture::Deleter),
+};
+
+pub const NEGATIVE_SELECTIVE_DELETER: DrawProperties = DrawProperties {
+	draw_priority: 2.1,
+	size: 1.0,
+	color: Color::srgb(0.6, 0.6, 1.0),
+	texture: Some(crate::assets::Texture::Deleter),
+};
+
+pub const POSITIVE_SELECTIVE_SLOW_DELETER: DrawProperties = DrawProperties {
+	draw_priority: 2.3,
+	size: 1.0,
+	color: Color::srgb(0.8, 0.5, 0.5),
+	texture: Some(crate::assets::Texture::Deleter),
+};
+
+pub const NEGATIVE_SELECTIVE_SLOW_DELETER: DrawProperties = DrawProperties {
+	draw_priority: 2.3,
+	size: 1.0,
+	color: Color::srgb(0.5, 0.5, 0.8),
+	texture: Some(crate::assets::Texture::Deleter),
+};
+
 pub const ATTRACTOR: DrawProperties = DrawProperties {
 	draw_priority: 1.5,
 	size: 15.0,
@@ -58,6 +136,34 @@ pub const ATTRACTOR: DrawProperties = DrawProperties {
 	texture: None,
 };
 
+pub const POSITIVE_SELECTIVE_ATTRACTOR: DrawProperties = DrawProperties {
+	draw_priority: 1.5,
+	size: 15.0,
+	color: Color::srgb(0.8, 0.0, 0.4),
+	texture: None,
+};
+
+pub const NEGATIVE_SELECTIVE_ATTRACTOR: DrawProperties = DrawProperties {
+	draw_priority: 1.5,
+	size: 15.0,
+	color: Color::srgb(0.4, 0.0, 0.8),
+	texture: None,
+};
+
+pub const POSITIVE_CHARGED_POLE: DrawProperties = DrawProperties {
+	draw_priority: 1.6,
+	size: 3.0,
+	color: Color::srgb(1.0, 0.2, 0.2),
+	texture: Some(crate::assets::Texture::Particle),
+};
+
+pub const NEGATIVE_CHARGED_POLE: DrawProperties = DrawProperties {
+	draw_priority: 1.6,
+	size: 3.0,
+	color: Color::srgb(0.2, 0.2, 1.0),
+	texture: Some(crate::assets::Texture::Particle),
+};
+
 pub const REPULSOR: DrawProperties = DrawProperties {
 	draw_priority: 1.4,
 	size: 16.0,
@@ -65,6 +171,55 @@ pub const REPULSOR: DrawProperties = DrawProperties {
 	texture: None,
 };
 
+pub const VORTEX: DrawProperties = DrawProperties {
+	draw_priority: 1.3,
+	size: 15.0,
+	color: Color::srgb(0.0, 0.7, 0.7),
+	texture: None,
+};
+
+pub const CLOCKWISE_VORTEX: DrawProperties = DrawProperties {
+	draw_priority: 1.3,
+	size: 15.0,
+	color: Color::srgb(0.0, 0.5, 0.8),
+	texture: None,
+};
+
+pub const WALL: DrawProperties = DrawProperties {
+	draw_priority: 3.0,
+	size: 1.0,
+	color: Color::srgb(0.8, 0.8, 0.8),
+	texture: None,
+};
+
+pub const FENCE: DrawProperties = DrawProperties {
+	draw_priority: 3.0,
+	size: 1.0,
+	color: Color::srgb(0.6, 0.45, 0.3),
+	texture: None,
+};
+
+pub const PORTAL: DrawProperties = DrawProperties {
+	draw_priority: 1.2,
+	size: 20.0,
+	color: Color::srgb(1.0, 0.6, 0.0),
+	texture: None,
+};
+
+pub const POSITIVE_CONVERTER: DrawProperties = DrawProperties {
+	draw_priority: 1.1,
+	size: 12.0,
+	color: Color::srgb(1.0, 0.3, 0.6),
+	texture: None,
+};
+
+pub const NEGATIVE_CONVERTER: DrawProperties = DrawProperties {
+	draw_priority: 1.1,
+	size: 12.0,
+	color: Color::srgb(0.3, 0.6, 1.0),
+	texture: None,
+};
+
 pub const PUSHER: DrawProperties = DrawProperties {
 	draw_priority: 5.0,
 	size: 1.0,
@@ -85,3 +240,31 @@ pub const NEGATIVE_EATER: DrawProperties = DrawProperties {
 	color: Color::srgb(0.75, 0.75, 1.0),
 	texture: None,
 };
+
+pub const POSITIVE_SWARM_EATER: DrawProperties = DrawProperties {
+	draw_priority: 4.0,
+	size: 10.0,
+	color: Color::srgb(1.0, 0.9, 0.6),
+	texture: None,
+};
+
+pub const NEGATIVE_SWARM_EATER: DrawProperties = DrawProperties {
+	draw_priority: 4.0,
+	size: 10.0,
+	color: Color::srgb(0.6, 0.9, 1.0),
+	texture: None,
+};
+
+pub const SENSOR: DrawProperties = DrawProperties {
+	draw_priority: 1.2,
+	size: 12.0,
+	color: Color::srgb(1.0, 1.0, 0.4),
+	texture: None,
+};
+
+pub const CHARGE_SENSOR: DrawProperties = DrawProperties {
+	draw_priority: 1.2,
+	size: 12.0,
+	color: Color::srgb(0.4, 1.0, 0.8),
+	texture: None,
+};
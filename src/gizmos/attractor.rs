@@ -1,13 +1,160 @@
-use bevy::prelude::*;
+use bevy::{ecs::system::EntityCommands, prelude::*};
 
 use crate::{
 	TIMESTEP, WindowDimensions, WrappingForce,
-	common::{calculate_force, offset_2d},
-	movement::{Movement, MovementTrait},
-	particle::Particle,
+	common::{Positive, calculate_force, offset_2d},
+	decay::{Charge, Inert},
+	draw_properties,
+	gizmos::{
+		EffectPainter, GizmoDefinition, GizmoParameters, GizmoType, GizmoVariant, GizmoVariants,
+		Inactive, Selective, affects_particle, overlay::EFFECT_AREA_COLOR,
+	},
+	movement::{Movement, MovementTrait, merge_speed},
+	particle::{ForceBarriers, Particle, particle_force, particle_force_reach},
 };
 
-#[derive(Component)]
+pub struct AttractorGizmo;
+
+impl GizmoDefinition for AttractorGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Attractor")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Neutral(GizmoVariant {
+			key: KeyCode::Digit2,
+			draw_properties: draw_properties::ATTRACTOR,
+		})
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert(Attractor::default());
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Attractor>(&[
+			"force",
+			"fall_off",
+			"proximity_cap",
+		]))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(attractor) = entity.get::<Attractor>() {
+			attractor.draw_effect_area(painter);
+		}
+	}
+	fn build(&self, app: &mut App) {
+		app.add_systems(FixedUpdate, activate_attractors.before(merge_speed));
+	}
+}
+
+/// An [`Attractor`] with negative force. Relies on [`AttractorGizmo`] for its systems.
+pub struct RepulsorGizmo;
+
+impl GizmoDefinition for RepulsorGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Repulsor")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Neutral(GizmoVariant {
+			key: KeyCode::Digit3,
+			draw_properties: draw_properties::REPULSOR,
+		})
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert(Attractor::repulsor());
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Attractor>(&[
+			"force",
+			"fall_off",
+			"proximity_cap",
+		]))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(attractor) = entity.get::<Attractor>() {
+			attractor.draw_effect_area(painter);
+		}
+	}
+}
+
+/// An [`Attractor`] that only attracts particles of its own polarity. Relies on [`AttractorGizmo`] for its systems.
+pub struct SelectiveAttractorGizmo;
+
+impl GizmoDefinition for SelectiveAttractorGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Selective attractor")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Polar {
+			negative: GizmoVariant {
+				key: KeyCode::Comma,
+				draw_properties: draw_properties::NEGATIVE_SELECTIVE_ATTRACTOR,
+			},
+			positive: GizmoVariant {
+				key: KeyCode::Slash,
+				draw_properties: draw_properties::POSITIVE_SELECTIVE_ATTRACTOR,
+			},
+		}
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert((Attractor::default(), Selective));
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Attractor>(&[
+			"force",
+			"fall_off",
+			"proximity_cap",
+		]))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(attractor) = entity.get::<Attractor>() {
+			attractor.draw_effect_area(painter);
+		}
+	}
+}
+
+/// Acts like a particle with a large charge that stays in place, repelling particles of its own polarity and attracting the others.
+pub struct ChargedPoleGizmo;
+
+impl GizmoDefinition for ChargedPoleGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Charged pole")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Polar {
+			negative: GizmoVariant {
+				key: KeyCode::KeyU,
+				draw_properties: draw_properties::NEGATIVE_CHARGED_POLE,
+			},
+			positive: GizmoVariant {
+				key: KeyCode::KeyP,
+				draw_properties: draw_properties::POSITIVE_CHARGED_POLE,
+			},
+		}
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert(ChargedPole::default());
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<ChargedPole>(&["charge"]))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(pole) = entity.get::<ChargedPole>() {
+			for threshold in EFFECT_THRESHOLDS {
+				painter.circle(
+					particle_force_reach(threshold / pole.charge),
+					EFFECT_AREA_COLOR,
+				);
+			}
+		}
+	}
+	fn build(&self, app: &mut App) {
+		app.add_systems(FixedUpdate, activate_charged_poles.before(merge_speed));
+	}
+}
+
+/// The amounts of movement per tick the effect overlay draws a ring for. The force never reaches zero, so there is no edge to draw.
+const EFFECT_THRESHOLDS: [f32; 3] = [1.0, 0.5, 0.25];
+
+#[derive(Component, Reflect)]
 pub struct Attractor {
 	force: f32,
 	fall_off: f32,
@@ -22,6 +169,19 @@ impl Attractor {
 			proximity_cap: 2.0,
 		}
 	}
+	/// The movement per tick this gives something at `offset` from it.
+	pub(super) fn pull(&self, offset: Vec2) -> Vec2 {
+		calculate_force(self.force, self.proximity_cap, self.fall_off, offset) * TIMESTEP
+	}
+	/// Draws rings at the distances where particles are moved by each of [`EFFECT_THRESHOLDS`] per tick.
+	fn draw_effect_area(&self, painter: &mut EffectPainter) {
+		for threshold in EFFECT_THRESHOLDS {
+			let radius = (self.force.abs() * TIMESTEP / threshold).powf(self.fall_off.recip());
+			if radius > self.proximity_cap {
+				painter.circle(radius, EFFECT_AREA_COLOR);
+			}
+		}
+	}
 }
 
 impl Default for Attractor {
@@ -34,28 +194,65 @@ impl Default for Attractor {
 	}
 }
 
+#[derive(Component, Reflect)]
+pub struct ChargedPole {
+	/// How many particles' worth of charge it has.
+	charge: f32,
+}
+
+impl Default for ChargedPole {
+	fn default() -> Self {
+		Self { charge: 20.0 }
+	}
+}
+
+/// Applies the same force a particle would, multiplied by the pole's charge, including not applying it across walls that block forces.
+fn activate_charged_poles(
+	window_dimensions: Res<WindowDimensions>,
+	wrapping: Res<WrappingForce>,
+	barriers: Res<ForceBarriers>,
+	poles: Query<(&ChargedPole, &Transform, Has<Positive>), Without<Inactive>>,
+	mut particles: Query<
+		(&mut Movement, &Transform, Has<Positive>, Option<&Charge>),
+		(With<Particle>, Without<Inert>),
+	>,
+) {
+	let wrap = wrapping.0.then_some(window_dimensions.0);
+	for (pole, pole_transform, positive) in &poles {
+		let pole_position = pole_transform.translation.truncate();
+		for (mut movement, particle_transform, particle_positive, charge) in &mut particles {
+			let offset = offset_2d(
+				particle_transform.translation.truncate(),
+				pole_position,
+				wrap,
+			);
+			if barriers.blocks(wrap, pole_position, offset) {
+				continue;
+			}
+			let charge = pole.charge * charge.map_or(1.0, |charge| charge.0);
+			movement.add(particle_force(offset, particle_positive, positive) * charge);
+		}
+	}
+}
+
 pub fn activate_attractors(
 	window_dimensions: Res<WindowDimensions>,
 	wrapping: Res<WrappingForce>,
-	attractors: Query<(&Attractor, &Transform)>,
-	mut particles: Query<(&mut Movement, &Transform), With<Particle>>,
+	attractors: Query<(&Attractor, &Transform, Has<Selective>, Has<Positive>), Without<Inactive>>,
+	mut particles: Query<(&mut Movement, &Transform, Has<Positive>), With<Particle>>,
 ) {
-	for (attractor, attractor_transform) in &attractors {
+	for (attractor, attractor_transform, selective, positive) in &attractors {
 		let attractor_position = attractor_transform.translation.truncate();
-		for (mut movement, particle_transform) in &mut particles {
+		for (mut movement, particle_transform, particle_positive) in &mut particles {
+			if !affects_particle(selective, positive, particle_positive) {
+				continue;
+			}
 			let offset = offset_2d(
 				attractor_position,
 				particle_transform.translation.truncate(),
 				wrapping.0.then_some(window_dimensions.0),
 			);
-			let force = calculate_force(
-				attractor.force,
-				attractor.proximity_cap,
-				attractor.fall_off,
-				offset,
-			) * TIMESTEP;
-
-			movement.add(force);
+			movement.add(attractor.pull(offset));
 		}
 	}
 }
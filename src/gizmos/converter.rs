@@ -0,0 +1,119 @@
+use bevy::{ecs::system::EntityCommands, prelude::*};
+
+use crate::{
+	TIMESTEP, WindowDimensions,
+	common::{Positive, wrapping_offset_2d},
+	draw_properties,
+	gizmos::{
+		EffectPainter, GizmoDefinition, GizmoParameters, GizmoType, GizmoVariant, GizmoVariants,
+		Inactive, overlay::EFFECT_AREA_COLOR,
+	},
+	particle::Particle,
+};
+
+pub struct ConverterGizmo;
+
+impl GizmoDefinition for ConverterGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Converter")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Polar {
+			negative: GizmoVariant {
+				key: KeyCode::Digit9,
+				draw_properties: draw_properties::NEGATIVE_CONVERTER,
+			},
+			positive: GizmoVariant {
+				key: KeyCode::Digit0,
+				draw_properties: draw_properties::POSITIVE_CONVERTER,
+			},
+		}
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert(Converter::default());
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Converter>(&["radius", "rate"]))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(converter) = entity.get::<Converter>() {
+			painter.circle(converter.radius, EFFECT_AREA_COLOR);
+		}
+	}
+	fn build(&self, app: &mut App) {
+		app.add_systems(
+			FixedUpdate,
+			(recharge_converters, activate_converters).chain(),
+		);
+	}
+}
+
+/// Gives particles of the opposing polarity in its radius its own polarity. Their colour follows, since it is set from [`Positive`] every frame.
+#[derive(Component, Reflect)]
+pub struct Converter {
+	radius: f32,
+	/// How many particles can be converted per second, or `None` to convert every particle in the radius right away.
+	rate: Option<f32>,
+	charge: f32,
+}
+
+impl Converter {
+	pub fn new(radius: f32, rate: Option<f32>) -> Self {
+		Self {
+			radius,
+			rate,
+			charge: 0.0,
+		}
+	}
+	fn can_convert(&self) -> bool {
+		self.rate.is_none() || self.charge >= 1.0
+	}
+}
+
+impl Default for Converter {
+	fn default() -> Self {
+		Self::new(60.0, Some(10.0))
+	}
+}
+
+pub fn activate_converters(
+	mut commands: Commands,
+	window_dimensions: Res<WindowDimensions>,
+	mut converters: Query<(&mut Converter, Has<Positive>, &Transform), Without<Inactive>>,
+	particles: Query<(Entity, Has<Positive>, &Transform), With<Particle>>,
+) {
+	'particle: for (particle, particle_positive, particle_transform) in &particles {
+		let particle_position = particle_transform.translation.truncate();
+		for (mut converter, converter_positive, converter_transform) in &mut converters {
+			if particle_positive == converter_positive || !converter.can_convert() {
+				continue;
+			}
+			let converter_position = converter_transform.translation.truncate();
+			let distance_squared =
+				wrapping_offset_2d(particle_position, converter_position, window_dimensions.0)
+					.length_squared();
+
+			if distance_squared < converter.radius.powi(2) {
+				// It may have been cancelled or deleted this tick.
+				if converter_positive {
+					commands.entity(particle).try_insert(Positive);
+				} else {
+					commands.entity(particle).try_remove::<Positive>();
+				}
+				if converter.rate.is_some() {
+					converter.charge -= 1.0;
+				}
+				continue 'particle;
+			}
+		}
+	}
+}
+
+/// Charge is capped at 1, so a converter that has been idle can't convert a burst of particles all at once.
+fn recharge_converters(mut converters: Query<&mut Converter>) {
+	for mut converter in &mut converters {
+		if let Some(rate) = converter.rate {
+			converter.charge = (converter.charge + rate * TIMESTEP).min(1.0);
+		}
+	}
+}
@@ -1,17 +1,161 @@
-use bevy::prelude::*;
+use bevy::{ecs::system::EntityCommands, prelude::*};
 
-use crate::{TIMESTEP, WindowDimensions, common::wrapping_offset_2d, particle::Particle};
+use crate::{
+	TIMESTEP, WindowDimensions,
+	common::{Positive, wrapping_offset_2d},
+	draw_properties,
+	gizmos::{
+		EffectPainter, GizmoDefinition, GizmoParameters, GizmoType, GizmoVariant, GizmoVariants,
+		Inactive, Selective, affects_particle, overlay::EFFECT_AREA_COLOR,
+	},
+	particle::Particle,
+	stats::EventTotals,
+};
 
-#[derive(Component)]
+pub struct DeleterGizmo;
+
+impl GizmoDefinition for DeleterGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Deleter")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Neutral(GizmoVariant {
+			key: KeyCode::Digit1,
+			draw_properties: draw_properties::DELETER,
+		})
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert(Deleter::default());
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Deleter>(&["radius"]))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(deleter) = entity.get::<Deleter>() {
+			painter.circle(deleter.radius, EFFECT_AREA_COLOR);
+		}
+	}
+	fn build(&self, app: &mut App) {
+		app.add_systems(FixedUpdate, activate_deleters);
+	}
+}
+
+pub struct SlowDeleterGizmo;
+
+impl GizmoDefinition for SlowDeleterGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Slow deleter")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Neutral(GizmoVariant {
+			key: KeyCode::Backquote,
+			draw_properties: draw_properties::SLOW_DELETER,
+		})
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert(SlowDeleter::default());
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<SlowDeleter>(&[
+			"rate",
+			"radius",
+			"max_charge",
+		]))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(deleter) = entity.get::<SlowDeleter>() {
+			painter.circle(deleter.radius, EFFECT_AREA_COLOR);
+		}
+	}
+	fn build(&self, app: &mut App) {
+		// Plain deleters go first, so both kinds don't despawn the same particle.
+		app.add_systems(
+			FixedUpdate,
+			(
+				recharge_slow_deleters,
+				activate_slow_deleters.after(activate_deleters),
+			)
+				.chain(),
+		);
+	}
+}
+
+/// A [`Deleter`] that only deletes particles of its own polarity. Relies on [`DeleterGizmo`] for its systems.
+pub struct SelectiveDeleterGizmo;
+
+impl GizmoDefinition for SelectiveDeleterGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Selective deleter")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Polar {
+			negative: GizmoVariant {
+				key: KeyCode::KeyH,
+				draw_properties: draw_properties::NEGATIVE_SELECTIVE_DELETER,
+			},
+			positive: GizmoVariant {
+				key: KeyCode::KeyJ,
+				draw_properties: draw_properties::POSITIVE_SELECTIVE_DELETER,
+			},
+		}
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert((Deleter::default(), Selective));
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Deleter>(&["radius"]))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(deleter) = entity.get::<Deleter>() {
+			painter.circle(deleter.radius, EFFECT_AREA_COLOR);
+		}
+	}
+}
+
+/// A [`SlowDeleter`] that only deletes particles of its own polarity. Relies on [`SlowDeleterGizmo`] for its systems.
+pub struct SelectiveSlowDeleterGizmo;
+
+impl GizmoDefinition for SelectiveSlowDeleterGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Selective slow deleter")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Polar {
+			negative: GizmoVariant {
+				key: KeyCode::KeyV,
+				draw_properties: draw_properties::NEGATIVE_SELECTIVE_SLOW_DELETER,
+			},
+			positive: GizmoVariant {
+				key: KeyCode::KeyB,
+				draw_properties: draw_properties::POSITIVE_SELECTIVE_SLOW_DELETER,
+			},
+		}
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert((SlowDeleter::default(), Selective));
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<SlowDeleter>(&[
+			"rate",
+			"radius",
+			"max_charge",
+		]))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(deleter) = entity.get::<SlowDeleter>() {
+			painter.circle(deleter.radius, EFFECT_AREA_COLOR);
+		}
+	}
+}
+
+#[derive(Component, Reflect)]
 pub struct Deleter {
-	radius_squared: f32,
+	radius: f32,
 }
 
 impl Deleter {
 	pub fn new(radius: f32) -> Self {
-		Self {
-			radius_squared: radius.powi(2),
-		}
+		Self { radius }
 	}
 }
 
@@ -24,37 +168,50 @@ impl Default for Deleter {
 pub fn activate_deleters(
 	mut commands: Commands,
 	window_dimensions: Res<WindowDimensions>,
-	deleters: Query<(&Deleter, &Transform)>,
-	particles: Query<(Entity, &Transform), With<Particle>>,
+	mut totals: ResMut<EventTotals>,
+	deleters: Query<(&Deleter, &Transform, Has<Selective>, Has<Positive>), Without<Inactive>>,
+	particles: Query<(Entity, &Transform, Has<Positive>), With<Particle>>,
 ) {
-	'particle: for (particle, particle_transform) in &particles {
+	'particle: for (particle, particle_transform, particle_positive) in &particles {
 		let particle_position = particle_transform.translation.truncate();
-		for (deleter, deleter_transform) in &deleters {
+		for (deleter, deleter_transform, selective, positive) in &deleters {
+			if !affects_particle(selective, positive, particle_positive) {
+				continue;
+			}
 			let deleter_position = deleter_transform.translation.truncate();
 			let distance_squared =
 				wrapping_offset_2d(particle_position, deleter_position, window_dimensions.0)
 					.length_squared();
 
-			if distance_squared < deleter.radius_squared {
+			if distance_squared < deleter.radius.powi(2) {
 				commands.entity(particle).despawn();
+				totals.deleted += 1;
 				continue 'particle;
 			}
 		}
 	}
 }
 
-#[derive(Component)]
+/// How many deletions a [`SlowDeleter`] can store up while there is nothing to delete.
+const MAX_SLOW_DELETER_CHARGE: f32 = 5.0;
+
+#[derive(Component, Reflect)]
 pub struct SlowDeleter {
-	radius_squared: f32,
+	radius: f32,
+	/// Deletions per second.
 	rate: f32,
+	/// How many deletions it can store up.
+	max_charge: f32,
+	/// Every whole unit is a deletion it can make.
 	charge: f32,
 }
 
 impl SlowDeleter {
 	pub fn new(radius: f32, rate: f32) -> Self {
 		Self {
-			radius_squared: radius.powi(2),
+			radius,
 			rate,
+			max_charge: MAX_SLOW_DELETER_CHARGE,
 			charge: 0.0,
 		}
 	}
@@ -69,13 +226,17 @@ impl Default for SlowDeleter {
 pub fn activate_slow_deleters(
 	mut commands: Commands,
 	window_dimensions: Res<WindowDimensions>,
-	mut deleters: Query<(&mut SlowDeleter, &Transform)>,
-	particles: Query<(Entity, &Transform), With<Particle>>,
+	mut totals: ResMut<EventTotals>,
+	mut deleters: Query<
+		(&mut SlowDeleter, &Transform, Has<Selective>, Has<Positive>),
+		Without<Inactive>,
+	>,
+	particles: Query<(Entity, &Transform, Has<Positive>), With<Particle>>,
 ) {
-	'particle: for (particle, particle_transform) in &particles {
+	'particle: for (particle, particle_transform, particle_positive) in &particles {
 		let particle_position = particle_transform.translation.truncate();
-		for (mut deleter, deleter_transform) in &mut deleters {
-			if deleter.charge < 1.0 {
+		for (mut deleter, deleter_transform, selective, positive) in &mut deleters {
+			if deleter.charge < 1.0 || !affects_particle(selective, positive, particle_positive) {
 				continue;
 			}
 			let deleter_position = deleter_transform.translation.truncate();
@@ -83,17 +244,62 @@ pub fn activate_slow_deleters(
 				wrapping_offset_2d(particle_position, deleter_position, window_dimensions.0)
 					.length_squared();
 
-			if distance_squared < deleter.radius_squared {
+			if distance_squared < deleter.radius.powi(2) {
 				commands.entity(particle).despawn();
-				deleter.charge = 0.0;
+				totals.deleted += 1;
+				deleter.charge -= 1.0;
 				continue 'particle;
 			}
 		}
 	}
 }
 
-pub(crate) fn recharge_slow_deleters(mut deleters: Query<&mut SlowDeleter>) {
+fn recharge_slow_deleters(mut deleters: Query<&mut SlowDeleter, Without<Inactive>>) {
 	for mut deleter in &mut deleters {
-		deleter.charge += deleter.rate / TIMESTEP;
+		deleter.charge = (deleter.charge + deleter.rate * TIMESTEP).min(deleter.max_charge);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Runs a slow deleter surrounded by more particles than it can delete for `seconds`, returning how many it deleted.
+	fn deletions_over(rate: f32, seconds: f32) -> u64 {
+		let mut world = World::new();
+		world.insert_resource(WindowDimensions(Vec2::splat(1000.0)));
+		world.init_resource::<EventTotals>();
+		world.spawn((
+			SlowDeleter::new(100.0, rate),
+			Transform::from_xyz(500.0, 500.0, 0.0),
+		));
+		let particle_count = (rate * seconds) as usize * 2 + 10;
+		for index in 0..particle_count {
+			let offset = Vec2::from_angle(index as f32) * 50.0;
+			world.spawn((
+				Particle,
+				Transform::from_xyz(500.0 + offset.x, 500.0 + offset.y, 0.0),
+			));
+		}
+
+		let mut schedule = Schedule::default();
+		schedule.add_systems((recharge_slow_deleters, activate_slow_deleters).chain());
+		for _ in 0..(seconds / TIMESTEP).round() as u32 {
+			schedule.run(&mut world);
+		}
+		world.resource::<EventTotals>().deleted
+	}
+
+	#[test]
+	fn deletes_at_configured_rate() {
+		for rate in [0.5, 2.0, 10.0, 120.0] {
+			let seconds = 10.0;
+			let deleted = deletions_over(rate, seconds) as f32;
+			let expected = rate * seconds;
+			assert!(
+				(deleted - expected).abs() <= 1.0,
+				"rate {rate}: deleted {deleted}, expected {expected}"
+			);
+		}
 	}
 }
@@ -0,0 +1,156 @@
+use std::f32::consts::PI;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+	cli::CommandLine,
+	common::Cursor,
+	gizmos::{GizmoRegistry, GizmoType, HoveredGizmo},
+	history::{Edit, GizmoState},
+	input::Action,
+	unwrap_or_return,
+};
+
+pub struct DraggingPlugin;
+
+impl Plugin for DraggingPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<SnapGrid>()
+			.init_resource::<Drag>()
+			.add_systems(Startup, set_grid_from_command_line)
+			.add_systems(Update, drag_gizmos);
+	}
+}
+
+/// Rotation snaps to multiples of this many radians.
+const ROTATION_SNAP: f32 = PI / 12.0;
+
+/// The spacing of the grid that dragged gizmos snap to while the snap modifier is held.
+#[derive(Resource)]
+pub struct SnapGrid(f32);
+
+impl SnapGrid {
+	fn snap(&self, position: Vec2) -> Vec2 {
+		(position / self.0).round() * self.0
+	}
+}
+
+impl Default for SnapGrid {
+	fn default() -> Self {
+		Self(20.0)
+	}
+}
+
+/// The gizmo being dragged, if any, and its state from before, so the drag can be undone.
+#[derive(Resource, Default)]
+struct Drag(Option<(Entity, DragKind, GizmoState)>);
+
+#[derive(Clone, Copy, PartialEq)]
+enum DragKind {
+	/// Moving a gizmo, keeping the offset from the cursor to it that it had when grabbed.
+	Moving { offset: Vec2 },
+	/// Turning a gizmo towards the cursor, if [`super::GizmoDefinition::can_turn`].
+	Rotating,
+}
+
+fn set_grid_from_command_line(command_line: Res<CommandLine>, mut grid: ResMut<SnapGrid>) {
+	if let Some(size) = command_line.grid_size {
+		grid.0 = size;
+	}
+}
+
+/// What [`drag_gizmos`] needs to know about an ongoing drag, or to start one.
+#[derive(SystemParam)]
+struct Dragging<'w> {
+	drag: ResMut<'w, Drag>,
+	grid: Res<'w, SnapGrid>,
+	hovered: Res<'w, HoveredGizmo>,
+}
+
+/// Left-dragging a gizmo moves it, and right-dragging a gizmo that can be turned turns it, the same way as when it was placed.
+fn drag_gizmos(
+	mut commands: Commands,
+	registry: Res<GizmoRegistry>,
+	cursor: Cursor,
+	action_state: Query<&ActionState<Action>>,
+	mut dragging: Dragging,
+	mut gizmos: Query<EntityMut, With<GizmoType>>,
+	mut edits: EventWriter<Edit>,
+) {
+	let action_state = action_state.single().unwrap();
+	let cursor_pos = unwrap_or_return!(cursor.position());
+	let snapping = action_state.pressed(&Action::SnapModifier);
+
+	if let Some(entity) = dragging.hovered.0.filter(|_| dragging.drag.0.is_none()) {
+		// Clicking with the toggle modifier switches the gizmo on or off instead.
+		if action_state.pressed(&Action::ToggleModifier) {
+			return;
+		}
+		let Ok(gizmo) = gizmos.get(entity) else {
+			return;
+		};
+		let definition = registry.get(*gizmo.get::<GizmoType>().unwrap());
+		let kind = if action_state.just_pressed(&Action::SpawnParticle) {
+			DragKind::Moving {
+				offset: gizmo.get::<Transform>().unwrap().translation.truncate() - cursor_pos,
+			}
+		} else if action_state.just_pressed(&Action::RotateGizmo)
+			&& definition.is_some_and(|gizmo| gizmo.can_turn())
+		{
+			DragKind::Rotating
+		} else {
+			return;
+		};
+		let before = GizmoState::capture(&gizmo, definition.and_then(|gizmo| gizmo.parameters()));
+		dragging.drag.0 = Some((entity, kind, before));
+	}
+
+	let Some((entity, kind, _)) = &dragging.drag.0 else {
+		return;
+	};
+	let (entity, kind) = (*entity, *kind);
+	let held = match kind {
+		DragKind::Moving { .. } => action_state.pressed(&Action::SpawnParticle),
+		DragKind::Rotating => action_state.pressed(&Action::RotateGizmo),
+	};
+	let Ok(mut gizmo) = gizmos.get_mut(entity) else {
+		dragging.drag.0 = None;
+		return;
+	};
+	if !held {
+		let (_, _, before) = dragging.drag.0.take().unwrap();
+		if before.transform() != *gizmo.get::<Transform>().unwrap() {
+			edits.write(Edit::Changed { entity, before });
+		}
+		return;
+	}
+
+	let gizmo_type = *gizmo.get::<GizmoType>().unwrap();
+	let mut transform = gizmo.get_mut::<Transform>().unwrap();
+	match kind {
+		DragKind::Moving { offset } => {
+			let mut position = cursor_pos + offset;
+			if snapping {
+				position = dragging.grid.snap(position);
+			}
+			let position = position.rem_euclid(cursor.window_dimensions());
+			transform.translation.x = position.x;
+			transform.translation.y = position.y;
+		}
+		DragKind::Rotating => {
+			let offset = cursor_pos - transform.translation.truncate();
+			let mut angle = offset.to_angle();
+			if snapping {
+				angle = (angle / ROTATION_SNAP).round() * ROTATION_SNAP;
+			}
+			transform.rotation = Quat::from_rotation_z(angle);
+			if let Some(gizmo) = registry.get(gizmo_type) {
+				gizmo.drag(
+					&mut commands.entity(entity),
+					Vec2::from_angle(angle) * offset.length(),
+				);
+			}
+		}
+	}
+}
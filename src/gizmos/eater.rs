@@ -1,33 +1,193 @@
-use bevy::prelude::*;
+use bevy::{ecs::system::EntityCommands, prelude::*};
 
 use crate::{
 	TIMESTEP, WindowDimensions, WrappingForce,
 	common::{Positive, calculate_force, circular_points, find_nearest_within_radius, offset_2d},
 	draw_properties,
-	movement::{Movement, MovementTrait},
-	particle::{Cancelled, NextBatch, Particle, spawn_particle_at_location},
+	gizmos::{
+		BeingPlaced, EffectPainter, GizmoDefinition, GizmoParameters, GizmoType, GizmoVariant,
+		GizmoVariants, Inactive, Selective, affects_particle,
+		attractor::Attractor,
+		overlay::{DORMANCY_COLOR, EFFECT_AREA_COLOR, PROGRESS_COLOR},
+		pusher::Pusher,
+	},
+	movement::{Movement, MovementTrait, merge_speed},
+	particle::{Cancelled, Particle, ParticleSpawner, spawn_particle_at_location},
+	stats::EventTotals,
 };
 
+pub struct EaterGizmo;
+
+impl GizmoDefinition for EaterGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Eater")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Polar {
+			negative: GizmoVariant {
+				key: KeyCode::BracketLeft,
+				draw_properties: draw_properties::NEGATIVE_EATER,
+			},
+			positive: GizmoVariant {
+				key: KeyCode::BracketRight,
+				draw_properties: draw_properties::POSITIVE_EATER,
+			},
+		}
+	}
+	fn has_movement(&self) -> bool {
+		true
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert(Eater::default());
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Eater>(&[
+			"target",
+			"pursuit_force",
+			"dormancy",
+			"spit_radius",
+			"repels",
+			"repulsion",
+			"follows_fields",
+		]))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		draw_eater_effect_area(entity, painter);
+	}
+	fn build(&self, app: &mut App) {
+		app.add_systems(
+			FixedUpdate,
+			(
+				(
+					eaters_chasing_particles,
+					eaters_repelling_each_other,
+					eaters_following_fields,
+				)
+					.before(merge_speed),
+				activate_eaters,
+				apply_eater_scale,
+				process_dormant_eaters,
+			),
+		);
+	}
+}
+
+/// An [`Eater`] that keeps away from other eaters and is moved by attractors and pushers, so groups of them spread out. Relies on [`EaterGizmo`] for its systems.
+pub struct SwarmEaterGizmo;
+
+impl GizmoDefinition for SwarmEaterGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Swarm eater")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Polar {
+			negative: GizmoVariant {
+				key: KeyCode::KeyS,
+				draw_properties: draw_properties::NEGATIVE_SWARM_EATER,
+			},
+			positive: GizmoVariant {
+				key: KeyCode::KeyF,
+				draw_properties: draw_properties::POSITIVE_SWARM_EATER,
+			},
+		}
+	}
+	fn has_movement(&self) -> bool {
+		true
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert(Eater::swarm());
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Eater>(&[
+			"target",
+			"pursuit_force",
+			"dormancy",
+			"spit_radius",
+			"repels",
+			"repulsion",
+			"follows_fields",
+		]))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		draw_eater_effect_area(entity, painter);
+	}
+}
+
+fn draw_eater_effect_area(entity: &EntityRef, painter: &mut EffectPainter) {
+	let Some(eater) = entity.get::<Eater>() else {
+		return;
+	};
+	painter.circle(EATER_RADIUS, EFFECT_AREA_COLOR);
+	painter.arc(
+		EATER_RADIUS + EFFECT_ARC_SPACING,
+		eater.eaten as f32 / eater.target as f32,
+		PROGRESS_COLOR,
+	);
+	if let Some(dormant) = entity.get::<Dormant>() {
+		painter.arc(
+			EATER_RADIUS + EFFECT_ARC_SPACING * 2.0,
+			dormant.0 / eater.dormancy,
+			DORMANCY_COLOR,
+		);
+	}
+}
+
 /// The radius inside the particle eater will eat particles.
 const EATER_RADIUS: f32 = 20.0;
+/// How long a particle eater stays dormant after filling up by default, in seconds.
+const DORMANCY_DURATION: f32 = 10.0;
+/// How far from its centre a particle eater spits out particles after filling up by default.
+const SPIT_RADIUS: f32 = 25.0;
+/// The theoretical force eaters that repel others push them away with at 1 pixel distance, by default.
+const BASE_REPULSION: f32 = 20_000.0;
+/// The gap between the rings the effect overlay draws around a particle eater.
+const EFFECT_ARC_SPACING: f32 = 4.0;
 /// The size the particle eater will be multiplied as it fills up. This is the size it would have at full, but it won't actually reach it, because being full shrinks it.
 const EATER_FULL_SCALE: f32 = 2.0;
-/// The theoretical amount of force applied to the particle eater at 1 pixel distance.
+/// The theoretical amount of force applied to the particle eater at 1 pixel distance, by default.
 const BASE_PURSUIT_FORCE: f32 = 5_000.0;
 /// The force will be applied as if it always has at least this distance.
 const PROXIMITY_FORCE_CAP: f32 = 5.0;
 /// The power to which the distance is raised to diminish force. A higher number means force more quickly diminishes with distance.
 const DIMINISHING_POWER: f32 = 1.5;
 
-#[derive(Component)]
+#[derive(Component, Reflect)]
 pub struct Eater {
 	eaten: u8,
 	target: u8,
+	/// The theoretical force pulling it towards each particle at 1 pixel distance.
+	pursuit_force: f32,
+	/// How long it stays dormant after filling up, in seconds.
+	dormancy: f32,
+	/// How far from its centre it spits out particles after filling up.
+	spit_radius: f32,
+	/// Whether it pushes other eaters away.
+	repels: bool,
+	/// The theoretical force it pushes other eaters away with at 1 pixel distance, if it [`Eater::repels`].
+	repulsion: f32,
+	/// Whether attractors and pushers move it the way they move particles.
+	follows_fields: bool,
 }
 
 impl Eater {
 	pub const fn new(target: u8) -> Self {
-		Self { eaten: 0, target }
+		Self {
+			eaten: 0,
+			target,
+			pursuit_force: BASE_PURSUIT_FORCE,
+			dormancy: DORMANCY_DURATION,
+			spit_radius: SPIT_RADIUS,
+			repels: false,
+			repulsion: BASE_REPULSION,
+			follows_fields: false,
+		}
+	}
+	pub const fn swarm() -> Self {
+		Self {
+			repels: true,
+			follows_fields: true,
+			..Self::new(10)
+		}
 	}
 	fn is_full(&self) -> bool {
 		self.eaten >= self.target
@@ -47,8 +207,12 @@ pub struct Dormant(f32);
 pub fn activate_eaters(
 	mut commands: Commands,
 	window_dimensions: Res<WindowDimensions>,
-	mut next_batch: ResMut<NextBatch>,
-	mut eaters: Query<(Entity, &mut Eater, Option<&Positive>, &Transform), Without<Dormant>>,
+	mut spawner: ParticleSpawner,
+	mut totals: ResMut<EventTotals>,
+	mut eaters: Query<
+		(Entity, &mut Eater, Option<&Positive>, &Transform),
+		(Without<Dormant>, Without<Inactive>),
+	>,
 	mut particles: Query<(Option<&Positive>, &mut Cancelled, &Transform), With<Particle>>,
 ) {
 	for (particle_positive, mut cancelled, particle_transform) in particles
@@ -73,12 +237,15 @@ pub fn activate_eaters(
 			) {
 			eater.eaten += 1;
 			cancelled.0 = true;
+			totals.eaten += 1;
 			if eater.is_full() {
-				commands.entity(entity).insert(Dormant(10.0));
-				for position in circular_points(eater_location, 25.0, eater.target as u32) {
+				commands.entity(entity).insert(Dormant(eater.dormancy));
+				for position in
+					circular_points(eater_location, eater.spit_radius, eater.target as u32)
+				{
 					spawn_particle_at_location(
 						&mut commands,
-						&mut next_batch,
+						&mut spawner,
 						position,
 						eater_positive.is_some(),
 					);
@@ -93,12 +260,12 @@ pub fn eaters_chasing_particles(
 	window_dimensions: Res<WindowDimensions>,
 	wrapping: Res<WrappingForce>,
 	mut eaters: Query<
-		(Option<&Positive>, &mut Movement, &Transform),
-		(With<Eater>, Without<Dormant>),
+		(&Eater, Option<&Positive>, &mut Movement, &Transform),
+		(Without<Dormant>, Without<Inactive>),
 	>,
 	particles: Query<(Option<&Positive>, &Transform), With<Particle>>,
 ) {
-	for (eater_positive, mut eater_movement, eater_transform) in &mut eaters {
+	for (eater, eater_positive, mut eater_movement, eater_transform) in &mut eaters {
 		let eater_position = eater_transform.translation.truncate();
 
 		for particle_transform in particles.iter().filter_map(|(positive, transform)| {
@@ -110,7 +277,7 @@ pub fn eaters_chasing_particles(
 				wrapping.0.then_some(window_dimensions.0),
 			);
 			let force = calculate_force(
-				BASE_PURSUIT_FORCE,
+				eater.pursuit_force,
 				PROXIMITY_FORCE_CAP,
 				DIMINISHING_POWER,
 				offset,
@@ -121,6 +288,78 @@ pub fn eaters_chasing_particles(
 	}
 }
 
+/// Eaters that [`Eater::repels`] push other eaters away with their [`Eater::repulsion`], dormant or not.
+fn eaters_repelling_each_other(
+	window_dimensions: Res<WindowDimensions>,
+	wrapping: Res<WrappingForce>,
+	mut eaters: Query<(&Eater, &mut Movement, &Transform), Without<Inactive>>,
+) {
+	let mut combinations = eaters.iter_combinations_mut();
+	while let Some(
+		[
+			(eater_a, mut movement_a, transform_a),
+			(eater_b, mut movement_b, transform_b),
+		],
+	) = combinations.fetch_next()
+	{
+		if !eater_a.repels && !eater_b.repels {
+			continue;
+		}
+		let offset = offset_2d(
+			transform_a.translation.truncate(),
+			transform_b.translation.truncate(),
+			wrapping.0.then_some(window_dimensions.0),
+		);
+		let force = |eater: &Eater| {
+			if !eater.repels {
+				return Vec2::ZERO;
+			}
+			calculate_force(
+				eater.repulsion,
+				PROXIMITY_FORCE_CAP,
+				DIMINISHING_POWER,
+				offset,
+			) * TIMESTEP
+		};
+		movement_a.add(force(eater_b));
+		movement_b.add(-force(eater_a));
+	}
+}
+
+/// Attractors and pushers move eaters that have [`Eater::follows_fields`] the same way they move particles of the eater's polarity.
+fn eaters_following_fields(
+	window_dimensions: Res<WindowDimensions>,
+	wrapping: Res<WrappingForce>,
+	attractors: Query<(&Attractor, &Transform, Has<Selective>, Has<Positive>), Without<Inactive>>,
+	pushers: Query<(&Pusher, &Transform), (Without<BeingPlaced>, Without<Inactive>)>,
+	mut eaters: Query<
+		(&Eater, Has<Positive>, &mut Movement, &Transform),
+		(Without<Dormant>, Without<Inactive>),
+	>,
+) {
+	for (eater, eater_positive, mut movement, eater_transform) in &mut eaters {
+		if !eater.follows_fields {
+			continue;
+		}
+		let eater_position = eater_transform.translation.truncate();
+		for (attractor, attractor_transform, selective, positive) in &attractors {
+			if affects_particle(selective, positive, eater_positive) {
+				let offset = offset_2d(
+					attractor_transform.translation.truncate(),
+					eater_position,
+					wrapping.0.then_some(window_dimensions.0),
+				);
+				movement.add(attractor.pull(offset));
+			}
+		}
+		for (pusher, pusher_transform) in &pushers {
+			if let Some(push) = pusher.push(pusher_transform, eater_position, window_dimensions.0) {
+				movement.add(push);
+			}
+		}
+	}
+}
+
 pub fn apply_eater_scale(mut eaters: Query<(&Eater, &mut Transform)>) {
 	for (eater, mut transform) in &mut eaters {
 		if eater.is_full() {
@@ -1,26 +1,277 @@
-use bevy::prelude::*;
+use std::f32::consts::{SQRT_2, TAU};
+
+use bevy::{ecs::system::EntityCommands, platform::collections::HashMap, prelude::*};
 use leafwing_input_manager::prelude::ActionState;
 
 use crate::{
 	TIMESTEP,
+	cli::CommandLine,
 	common::Positive,
+	draw_properties,
+	gizmos::{
+		BeingPlaced, EffectPainter, GizmoDefinition, GizmoParameters, GizmoType, GizmoVariant,
+		GizmoVariants, Inactive, PlacementStyle, overlay::EFFECT_AREA_COLOR,
+	},
+	history::Edit,
 	input::Action,
-	particle::{NextBatch, Particle, spawn_particle_at_location},
+	movement::{Inertia, Movement, MovementTrait, merge_speed},
+	particle::{Lifetime, Particle, ParticleSpawner, spawn_particle_at_location},
+	stats::EventTotals,
 };
 
-#[derive(Component)]
+pub struct EmitterGizmo;
+
+impl GizmoDefinition for EmitterGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Emitter")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Polar {
+			negative: GizmoVariant {
+				key: KeyCode::Minus,
+				draw_properties: draw_properties::NEGATIVE_EMITTER,
+			},
+			positive: GizmoVariant {
+				key: KeyCode::Equal,
+				draw_properties: draw_properties::POSITIVE_EMITTER,
+			},
+		}
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert(Emitter::default());
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Emitter>(EMITTER_PARAMETERS))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(emitter) = entity.get::<Emitter>() {
+			emitter.draw_effect_area(painter);
+		}
+	}
+	fn build(&self, app: &mut App) {
+		app.add_systems(Update, apply_command_line_to_emitters)
+			.add_systems(FixedUpdate, activate_emitters)
+			.add_systems(FixedUpdate, propel_launched_particles.before(merge_speed));
+	}
+}
+
+/// Relies on the systems added by [`EmitterGizmo`].
+pub struct DirectionalEmitterGizmo;
+
+impl GizmoDefinition for DirectionalEmitterGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Directional emitter")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Polar {
+			negative: GizmoVariant {
+				key: KeyCode::Semicolon,
+				draw_properties: draw_properties::NEGATIVE_DIRECTIONAL_EMITTER,
+			},
+			positive: GizmoVariant {
+				key: KeyCode::Quote,
+				draw_properties: draw_properties::POSITIVE_DIRECTIONAL_EMITTER,
+			},
+		}
+	}
+	fn placement_style(&self) -> PlacementStyle {
+		PlacementStyle::WithRotation
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert(Emitter::new().with_launch(Launch {
+			speed: 150.0,
+			spread: 0.3,
+		}));
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Emitter>(EMITTER_PARAMETERS))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(emitter) = entity.get::<Emitter>() {
+			emitter.draw_effect_area(painter);
+		}
+	}
+}
+
+/// Relies on the systems added by [`EmitterGizmo`].
+pub struct BurstEmitterGizmo;
+
+impl GizmoDefinition for BurstEmitterGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Burst emitter")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Polar {
+			negative: GizmoVariant {
+				key: KeyCode::KeyN,
+				draw_properties: draw_properties::NEGATIVE_BURST_EMITTER,
+			},
+			positive: GizmoVariant {
+				key: KeyCode::KeyM,
+				draw_properties: draw_properties::POSITIVE_BURST_EMITTER,
+			},
+		}
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert(
+			Emitter::new()
+				.with_interval(1.5)
+				.with_burst(12)
+				.with_shape(EmitterShape::Ring { radius: 20.0 }),
+		);
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Emitter>(EMITTER_PARAMETERS))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(emitter) = entity.get::<Emitter>() {
+			emitter.draw_effect_area(painter);
+		}
+	}
+}
+
+/// Relies on the systems added by [`EmitterGizmo`].
+pub struct AreaEmitterGizmo;
+
+impl GizmoDefinition for AreaEmitterGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Area emitter")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Polar {
+			negative: GizmoVariant {
+				key: KeyCode::KeyK,
+				draw_properties: draw_properties::NEGATIVE_AREA_EMITTER,
+			},
+			positive: GizmoVariant {
+				key: KeyCode::KeyL,
+				draw_properties: draw_properties::POSITIVE_AREA_EMITTER,
+			},
+		}
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert(Emitter::new().with_shape(EmitterShape::Area { radius: 80.0 }));
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Emitter>(EMITTER_PARAMETERS))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(emitter) = entity.get::<Emitter>() {
+			emitter.draw_effect_area(painter);
+		}
+	}
+}
+
+/// Fields that only some emitters have, like the radius of their shape, are left out for the others.
+const EMITTER_PARAMETERS: &[&str] = &[
+	"interval",
+	"burst",
+	"budget",
+	"lifetime",
+	"shape.radius",
+	"launch.0.speed",
+	"launch.0.spread",
+];
+
+/// How long the lines showing the cone a directional emitter launches particles in are drawn.
+const LAUNCH_CONE_LENGTH: f32 = 60.0;
+
+/// How long launched particles keep being pushed, in seconds, when inertia is off.
+const LAUNCH_DURATION: f32 = 0.5;
+
+/// Spreads emitted particles evenly without needing randomness: each next value is as far from the previous ones as it can be.
+const GOLDEN_RATIO_FRACTION: f32 = 0.618_034;
+
+#[derive(Component, Reflect)]
 pub struct Emitter {
 	interval: f32,
+	/// How many particles are emitted every interval.
+	burst: u32,
+	shape: EmitterShape,
+	launch: Option<Launch>,
+	/// The most particles it has emitted that can be alive at once. It pauses while at its budget. Defaults to the one given on the command line, if any.
+	budget: Option<u32>,
+	/// How many seconds particles it emits live for, at the default decay rate. Defaults to the one given on the command line, if any.
+	lifetime: Option<f32>,
 	time_since_emitting: f32,
+	/// How many particles this has emitted, used to pick where the next one goes.
+	emitted: u32,
 }
 
 impl Emitter {
 	pub const fn new() -> Self {
 		Self {
 			interval: 0.1,
+			burst: 1,
+			shape: EmitterShape::Point,
+			launch: None,
+			budget: None,
+			lifetime: None,
 			time_since_emitting: 0.0,
+			emitted: 0,
+		}
+	}
+	pub const fn with_interval(self, interval: f32) -> Self {
+		Self { interval, ..self }
+	}
+	pub const fn with_burst(self, burst: u32) -> Self {
+		Self {
+			burst: if burst == 0 { 1 } else { burst },
+			..self
+		}
+	}
+	pub const fn with_shape(self, shape: EmitterShape) -> Self {
+		Self { shape, ..self }
+	}
+	pub const fn with_launch(self, launch: Launch) -> Self {
+		Self {
+			launch: Some(launch),
+			..self
+		}
+	}
+	/// The offset from the emitter's centre of the next particle.
+	fn spawn_offset(&self) -> Vec2 {
+		let count = self.emitted as f32;
+		match self.shape {
+			EmitterShape::Point => Vec2::ZERO,
+			EmitterShape::Ring { radius } => {
+				// Evenly spaced within a burst, and turned a little further for every burst.
+				let bursts = (self.emitted / self.burst) as f32;
+				let turns = (count + bursts * GOLDEN_RATIO_FRACTION) / self.burst as f32;
+				Vec2::from_angle(turns * TAU) * radius
+			}
+			EmitterShape::Area { radius } => {
+				// The square root makes up for outer rings being longer, so the disc is covered evenly.
+				let distance = radius * (count * SQRT_2).fract().sqrt();
+				Vec2::from_angle(count * GOLDEN_RATIO_FRACTION * TAU) * distance
+			}
+		}
+	}
+	/// Draws the disc or ring particles appear in, and the cone they are launched in.
+	fn draw_effect_area(&self, painter: &mut EffectPainter) {
+		match self.shape {
+			EmitterShape::Point => {}
+			EmitterShape::Ring { radius } | EmitterShape::Area { radius } => {
+				painter.circle(radius, EFFECT_AREA_COLOR);
+			}
+		}
+		if let Some(Launch { spread, .. }) = self.launch {
+			for angle in [-spread / 2.0, spread / 2.0] {
+				painter.line(
+					Vec2::ZERO,
+					Vec2::from_angle(angle) * LAUNCH_CONE_LENGTH,
+					EFFECT_AREA_COLOR,
+				);
+			}
 		}
 	}
+	/// The velocity the next particle is launched with, given the direction the emitter faces.
+	fn launch_velocity(&self, direction: Vec2) -> Option<Vec2> {
+		self.launch.map(|Launch { speed, spread }| {
+			let fraction = (self.emitted as f32 * GOLDEN_RATIO_FRACTION).fract();
+			Vec2::from_angle((fraction - 0.5) * spread).rotate(direction) * speed
+		})
+	}
 }
 
 impl Default for Emitter {
@@ -29,26 +280,92 @@ impl Default for Emitter {
 	}
 }
 
+/// Where around an [`Emitter`] particles appear.
+#[derive(Debug, Clone, Copy, Reflect)]
+pub enum EmitterShape {
+	Point,
+	/// On the edge of a circle.
+	Ring {
+		radius: f32,
+	},
+	/// Anywhere within a circle.
+	Area {
+		radius: f32,
+	},
+}
+
+/// Makes an [`Emitter`] launch particles in the direction it faces.
+#[derive(Debug, Clone, Copy, Reflect)]
+pub struct Launch {
+	/// In units/second.
+	pub speed: f32,
+	/// The angle of the cone particles are launched in, in radians.
+	pub spread: f32,
+}
+
+/// The emitter a particle came from, to count it against that emitter's budget.
+#[derive(Component)]
+pub struct EmittedBy(pub Entity);
+
+/// A particle that was launched by an emitter and is still being pushed along.
+#[derive(Component)]
+struct Launched {
+	/// In units/second.
+	velocity: Vec2,
+	remaining: f32,
+}
+
 pub fn activate_emitters(
 	mut commands: Commands,
 	limit: Res<ParticleLimit>,
-	mut next_batch: ResMut<NextBatch>,
-	particles: Query<(), With<Particle>>,
-	mut emitters: Query<(&mut Emitter, Option<&Positive>, &Transform)>,
+	mut spawner: ParticleSpawner,
+	mut totals: ResMut<EventTotals>,
+	particles: Query<Option<&EmittedBy>, With<Particle>>,
+	mut emitters: Query<
+		(Entity, &mut Emitter, Option<&Positive>, &Transform),
+		(Without<BeingPlaced>, Without<Inactive>),
+	>,
 ) {
-	if particles.iter().len() >= limit.current() as usize {
+	let mut capacity = (limit.current() as usize).saturating_sub(particles.iter().len());
+	if capacity == 0 {
 		return;
 	}
+	let mut alive = HashMap::<Entity, u32>::new();
+	for emitted_by in particles.iter().flatten() {
+		*alive.entry(emitted_by.0).or_default() += 1;
+	}
 
-	for (mut emitter, positive, transform) in &mut emitters {
+	for (entity, mut emitter, positive, transform) in &mut emitters {
 		let location = transform.translation.truncate();
+		let direction = (transform.rotation * Vec3::X).truncate();
+		let alive = alive.entry(entity).or_default();
 		if emitter.time_since_emitting > emitter.interval {
-			spawn_particle_at_location(
-				&mut commands,
-				&mut next_batch,
-				location,
-				positive.is_some(),
-			);
+			// Whatever doesn't fit under the limit or budget is skipped, rather than emitted a tick later.
+			for _ in 0..emitter.burst {
+				if capacity == 0 || emitter.budget.is_some_and(|budget| *alive >= budget) {
+					break;
+				}
+				let particle = spawn_particle_at_location(
+					&mut commands,
+					&mut spawner,
+					location + emitter.spawn_offset(),
+					positive.is_some(),
+				);
+				commands.entity(particle).insert(EmittedBy(entity));
+				if let Some(lifetime) = emitter.lifetime {
+					commands.entity(particle).insert(Lifetime(lifetime));
+				}
+				if let Some(velocity) = emitter.launch_velocity(direction) {
+					commands.entity(particle).insert(Launched {
+						velocity,
+						remaining: LAUNCH_DURATION,
+					});
+				}
+				totals.emitted += 1;
+				emitter.emitted = emitter.emitted.wrapping_add(1);
+				capacity -= 1;
+				*alive += 1;
+			}
 			emitter.time_since_emitting -= emitter.interval;
 		} else {
 			emitter.time_since_emitting += TIMESTEP;
@@ -56,6 +373,42 @@ pub fn activate_emitters(
 	}
 }
 
+/// Newly placed emitters get the budget and lifetime given on the command line, if any.
+fn apply_command_line_to_emitters(
+	command_line: Res<CommandLine>,
+	mut emitters: Query<&mut Emitter, Added<Emitter>>,
+) {
+	for mut emitter in &mut emitters {
+		if let Some(budget) = command_line.emitter_budget {
+			emitter.budget = Some(budget);
+		}
+		if let Some(lifetime) = command_line.emitter_lifetime {
+			emitter.lifetime = Some(lifetime);
+		}
+	}
+}
+
+/// Without inertia, movement is cleared every tick, so launched particles are pushed for a while, less and less. With inertia, one push is enough.
+fn propel_launched_particles(
+	mut commands: Commands,
+	inertia: Res<Inertia>,
+	mut particles: Query<(Entity, &mut Movement, &mut Launched)>,
+) {
+	for (entity, mut movement, mut launched) in &mut particles {
+		let displacement = launched.velocity * TIMESTEP;
+		if inertia.is_enabled() {
+			movement.add(inertia.movement(displacement));
+			commands.entity(entity).remove::<Launched>();
+			continue;
+		}
+		movement.add(displacement * (launched.remaining / LAUNCH_DURATION));
+		launched.remaining -= TIMESTEP;
+		if launched.remaining <= 0.0 {
+			commands.entity(entity).remove::<Launched>();
+		}
+	}
+}
+
 #[derive(Resource)]
 pub struct ParticleLimit(u32);
 
@@ -69,6 +422,9 @@ impl ParticleLimit {
 	pub fn current(&self) -> u32 {
 		self.0
 	}
+	pub fn set(&mut self, limit: u32) {
+		self.0 = limit;
+	}
 }
 
 impl Default for ParticleLimit {
@@ -79,16 +435,21 @@ impl Default for ParticleLimit {
 
 pub fn adjust_particle_limit(
 	mut limit: ResMut<ParticleLimit>,
+	mut edits: EventWriter<Edit>,
 	action_state: Query<&ActionState<Action>>,
 ) {
 	let action_state = action_state.single().unwrap();
 
+	let before = limit.current();
 	match (
 		action_state.just_pressed(&Action::RaiseParticleLimit),
 		action_state.just_pressed(&Action::LowerParticleLimit),
 	) {
 		(true, false) => limit.raise(),
 		(false, true) => limit.lower(),
-		_ => (),
+		_ => return,
+	}
+	if limit.current() != before {
+		edits.write(Edit::ParticleLimit(before));
 	}
 }
@@ -0,0 +1,321 @@
+use bevy::{ecs::system::SystemParam, prelude::*, window::PrimaryWindow};
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+	CLICK_RADIUS,
+	common::{Cursor, find_entity_by_cursor},
+	gizmos::{BeingPlaced, GizmoParameters, GizmoRegistry, GizmoType, field_label},
+	history::{Edit, GizmoState},
+	input::Action,
+};
+
+pub struct InspectorPlugin;
+
+impl Plugin for InspectorPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<HoveredGizmo>()
+			.init_resource::<SelectedGizmo>()
+			.add_systems(Startup, set_up_inspector)
+			.add_systems(
+				Update,
+				(
+					update_hovered_gizmo,
+					(select_gizmo, cycle_adjusted_field).chain(),
+					scale_hovered_gizmo,
+					(update_tooltip, update_inspector, draw_selection),
+				)
+					.chain(),
+			);
+	}
+}
+
+/// How much one notch of the mouse wheel multiplies a gizmo's primary parameter by.
+const SCROLL_FACTOR: f32 = 1.1;
+/// Touchpads report many small scrolls, and some mice report pixels instead of notches, so each frame's scroll is limited to this many notches.
+const MAX_SCROLL_PER_FRAME: f32 = 3.0;
+/// Scrolls less than this many seconds apart, of the same field of the same gizmo, are one gesture, undone all at once.
+const SCROLL_GESTURE_GAP: f32 = 0.5;
+const TOOLTIP_OFFSET: Vec2 = Vec2::new(15.0, 15.0);
+const SELECTION_COLOR: Color = Color::srgba(1.0, 1.0, 0.4, 0.8);
+
+/// The gizmo under the cursor, if any. Clicking on it selects and grabs it instead of spawning a particle.
+#[derive(Resource, Default)]
+pub struct HoveredGizmo(pub Option<Entity>);
+
+/// The gizmo shown in the inspector panel.
+#[derive(Resource, Default)]
+pub(super) struct SelectedGizmo {
+	pub(super) entity: Option<Entity>,
+	/// The field scrolling over it adjusts, if one was picked instead of its primary one.
+	field: Option<String>,
+}
+
+impl SelectedGizmo {
+	/// The field scrolling over the gizmo adjusts.
+	fn adjusted_field<'a>(&'a self, entity: Entity, parameters: &GizmoParameters) -> &'a str {
+		match &self.field {
+			Some(field) if self.entity == Some(entity) => field,
+			_ => parameters.primary(),
+		}
+	}
+}
+
+#[derive(Component)]
+struct Tooltip;
+
+#[derive(Component)]
+struct InspectorPanel;
+
+fn set_up_inspector(mut commands: Commands, asset_server: Res<AssetServer>) {
+	let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+	commands.spawn((
+		Text::default(),
+		TextFont::from_font(font.clone()).with_font_size(14.0),
+		TextColor(Color::WHITE),
+		BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+		Node {
+			display: Display::None,
+			position_type: PositionType::Absolute,
+			padding: UiRect::all(Val::Px(4.0)),
+			..default()
+		},
+		Tooltip,
+	));
+	commands.spawn((
+		Text::default(),
+		TextFont::from_font(font).with_font_size(18.0),
+		TextColor(Color::WHITE),
+		BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+		Node {
+			display: Display::None,
+			position_type: PositionType::Absolute,
+			top: Val::Px(150.0),
+			right: Val::Px(15.0),
+			padding: UiRect::all(Val::Px(6.0)),
+			..default()
+		},
+		InspectorPanel,
+	));
+}
+
+fn update_hovered_gizmo(
+	cursor: Cursor,
+	gizmos: Query<(Entity, &Transform), (With<GizmoType>, Without<BeingPlaced>)>,
+	mut hovered: ResMut<HoveredGizmo>,
+) {
+	hovered.0 = cursor.position().and_then(|cursor_pos| {
+		find_entity_by_cursor(cursor_pos, cursor.window_dimensions(), gizmos)
+	});
+}
+
+/// Clicking a gizmo selects it, and clicking anywhere else deselects it.
+fn select_gizmo(
+	action_state: Query<&ActionState<Action>>,
+	hovered: Res<HoveredGizmo>,
+	mut selected: ResMut<SelectedGizmo>,
+) {
+	let action_state = action_state.single().unwrap();
+	if !action_state.just_pressed(&Action::SpawnParticle)
+		|| action_state.pressed(&Action::ToggleModifier)
+	{
+		return;
+	}
+	if selected.entity != hovered.0 {
+		selected.field = None;
+	}
+	selected.entity = hovered.0;
+}
+
+/// Picks the next field of the selected gizmo for scrolling over it to adjust, looping around.
+fn cycle_adjusted_field(
+	registry: Res<GizmoRegistry>,
+	action_state: Query<&ActionState<Action>>,
+	mut selected: ResMut<SelectedGizmo>,
+	gizmos: Query<EntityRef, With<GizmoType>>,
+) {
+	if !action_state
+		.single()
+		.unwrap()
+		.just_pressed(&Action::CycleAdjustedField)
+	{
+		return;
+	}
+	let Some(entity) = selected.entity.and_then(|entity| gizmos.get(entity).ok()) else {
+		return;
+	};
+	let Some(parameters) = entity
+		.get::<GizmoType>()
+		.and_then(|gizmo_type| registry.get(*gizmo_type))
+		.and_then(|gizmo| gizmo.parameters())
+	else {
+		return;
+	};
+	let names: Vec<String> = parameters
+		.describe(&entity)
+		.into_iter()
+		.map(|(name, _)| name)
+		.collect();
+	let current = selected.adjusted_field(entity.id(), &parameters);
+	let next = names
+		.iter()
+		.position(|name| name == current)
+		.map_or(0, |index| (index + 1) % names.len());
+	selected.field = names.get(next).cloned();
+}
+
+/// The mouse wheel, and which gizmo and field it last scrolled, and when.
+#[derive(SystemParam)]
+struct Scrolling<'w, 's> {
+	action_state: Query<'w, 's, &'static ActionState<Action>>,
+	time: Res<'w, Time>,
+	last: Local<'s, Option<(Entity, String, f32)>>,
+}
+
+impl Scrolling<'_, '_> {
+	/// How many notches it scrolled this frame.
+	fn amount(&self) -> f32 {
+		self.action_state
+			.single()
+			.unwrap()
+			.value(&Action::ScaleGizmo)
+			.clamp(-MAX_SCROLL_PER_FRAME, MAX_SCROLL_PER_FRAME)
+	}
+	/// Records scrolling `field` of `entity`, returning whether that starts a new gesture rather than continuing the last one.
+	fn starts_gesture(&mut self, entity: Entity, field: &str) -> bool {
+		let now = self.time.elapsed_secs();
+		let continues = self
+			.last
+			.as_ref()
+			.is_some_and(|(last_entity, last_field, last_time)| {
+				*last_entity == entity
+					&& last_field == field
+					&& now - last_time < SCROLL_GESTURE_GAP
+			});
+		*self.last = Some((entity, field.to_owned(), now));
+		!continues
+	}
+}
+
+/// Only the start of a scroll gesture makes an edit, so undoing it undoes the whole gesture.
+fn scale_hovered_gizmo(
+	mut commands: Commands,
+	registry: Res<GizmoRegistry>,
+	mut scrolling: Scrolling,
+	hovered: Res<HoveredGizmo>,
+	selected: Res<SelectedGizmo>,
+	gizmos: Query<EntityRef, With<GizmoType>>,
+	mut edits: EventWriter<Edit>,
+) {
+	let scroll = scrolling.amount();
+	if scroll == 0.0 {
+		return;
+	}
+	let Some(entity) = hovered.0.and_then(|entity| gizmos.get(entity).ok()) else {
+		return;
+	};
+	if let Some(parameters) = entity
+		.get::<GizmoType>()
+		.and_then(|gizmo_type| registry.get(*gizmo_type))
+		.and_then(|gizmo| gizmo.parameters())
+	{
+		let field = selected.adjusted_field(entity.id(), &parameters);
+		if scrolling.starts_gesture(entity.id(), field) {
+			edits.write(Edit::Changed {
+				entity: entity.id(),
+				before: GizmoState::capture(&entity, Some(parameters)),
+			});
+		}
+		parameters.scale(
+			&mut commands.entity(entity.id()),
+			field,
+			SCROLL_FACTOR.powf(scroll),
+		);
+	}
+}
+
+fn update_tooltip(
+	window: Query<&Window, With<PrimaryWindow>>,
+	registry: Res<GizmoRegistry>,
+	hovered: Res<HoveredGizmo>,
+	selected: Res<SelectedGizmo>,
+	gizmos: Query<EntityRef, With<GizmoType>>,
+	mut tooltip: Query<(&mut Text, &mut Node), (With<Tooltip>, Without<GizmoType>)>,
+) {
+	let (mut text, mut node) = tooltip.single_mut().unwrap();
+	let cursor_pos = window
+		.single()
+		.ok()
+		.and_then(|window| window.cursor_position());
+	let description = hovered
+		.0
+		.and_then(|entity| gizmos.get(entity).ok())
+		.zip(cursor_pos);
+	let Some((entity, cursor_pos)) = description else {
+		node.display = Display::None;
+		return;
+	};
+
+	let gizmo_type = entity.get::<GizmoType>().unwrap();
+	text.0 = String::from(gizmo_type.0);
+	if let Some(parameters) = registry
+		.get(*gizmo_type)
+		.and_then(|gizmo| gizmo.parameters())
+		&& let Some((name, value)) = parameters
+			.describe(&entity)
+			.into_iter()
+			.find(|(name, _)| name == selected.adjusted_field(entity.id(), &parameters))
+	{
+		text.0 += &format!("\n{}: {value} (scroll to change)", field_label(&name));
+	}
+	node.display = Display::Flex;
+	node.left = Val::Px(cursor_pos.x + TOOLTIP_OFFSET.x);
+	node.top = Val::Px(cursor_pos.y + TOOLTIP_OFFSET.y);
+}
+
+fn update_inspector(
+	registry: Res<GizmoRegistry>,
+	mut selected: ResMut<SelectedGizmo>,
+	gizmos: Query<EntityRef, With<GizmoType>>,
+	mut panel: Query<(&mut Text, &mut Node), (With<InspectorPanel>, Without<GizmoType>)>,
+) {
+	let (mut text, mut node) = panel.single_mut().unwrap();
+	let Some(entity) = selected.entity.and_then(|entity| gizmos.get(entity).ok()) else {
+		// The selected gizmo may have been deleted.
+		selected.entity = None;
+		node.display = Display::None;
+		return;
+	};
+
+	let gizmo_type = entity.get::<GizmoType>().unwrap();
+	text.0 = String::from(gizmo_type.0);
+	if let Some(parameters) = registry
+		.get(*gizmo_type)
+		.and_then(|gizmo| gizmo.parameters())
+	{
+		let adjusted = selected.adjusted_field(entity.id(), &parameters);
+		for (name, value) in parameters.describe(&entity) {
+			text.0 += &format!("\n{}: {value}", field_label(&name));
+			if name == adjusted {
+				text.0 += " (scroll to change)";
+			}
+		}
+	}
+	node.display = Display::Flex;
+}
+
+fn draw_selection(
+	mut gizmos: Gizmos,
+	selected: Res<SelectedGizmo>,
+	transforms: Query<&Transform, With<GizmoType>>,
+) {
+	if let Some(transform) = selected
+		.entity
+		.and_then(|entity| transforms.get(entity).ok())
+	{
+		gizmos.circle_2d(
+			transform.translation.truncate(),
+			CLICK_RADIUS,
+			SELECTION_COLOR,
+		);
+	}
+}
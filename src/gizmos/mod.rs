@@ -1,74 +1,343 @@
-use bevy::{ecs::system::EntityCommands, prelude::*, window::PrimaryWindow};
-use deleter::{SlowDeleter, activate_slow_deleters, recharge_slow_deleters};
+use bevy::{
+	ecs::{
+		component::Mutable,
+		entity_disabling::Disabled,
+		system::{EntityCommands, SystemParam},
+	},
+	platform::collections::HashSet,
+	prelude::*,
+	reflect::{DynamicEnum, DynamicVariant, ReflectMut, ReflectRef, TypeInfo, VariantType},
+};
 use leafwing_input_manager::prelude::ActionState;
 
 use crate::{
-	WindowDimensions,
 	assets::TextureMap,
-	common::{Positive, find_entity_by_cursor},
-	draw_properties::{self, DrawProperties},
-	gizmos::pusher::{Pusher, activate_pushers},
+	common::{Cursor, Positive, find_entity_by_cursor},
+	draw_properties::DrawProperties,
+	history::Edit,
 	input::Action,
-	movement::{Movement, merge_speed},
+	movement::Movement,
 	unwrap_or_return,
 };
 
 use self::{
-	attractor::{Attractor, activate_attractors},
-	deleter::{Deleter, activate_deleters},
-	eater::{
-		Eater, activate_eaters, apply_eater_scale, eaters_chasing_particles, process_dormant_eaters,
+	attractor::{AttractorGizmo, ChargedPoleGizmo, RepulsorGizmo, SelectiveAttractorGizmo},
+	converter::ConverterGizmo,
+	deleter::{DeleterGizmo, SelectiveDeleterGizmo, SelectiveSlowDeleterGizmo, SlowDeleterGizmo},
+	dragging::DraggingPlugin,
+	eater::{EaterGizmo, SwarmEaterGizmo},
+	emitter::{
+		AreaEmitterGizmo, BurstEmitterGizmo, DirectionalEmitterGizmo, EmitterGizmo,
+		adjust_particle_limit,
 	},
-	emitter::{Emitter, activate_emitters, adjust_particle_limit},
+	inspector::InspectorPlugin,
+	motion::MotionPlugin,
+	overlay::{EffectPainter, OverlayPlugin},
+	portal::PortalGizmo,
+	pusher::PusherGizmo,
+	sensor::{ChargeSensorGizmo, SensorGizmo},
+	toggling::TogglingPlugin,
+	vortex::{ClockwiseVortexGizmo, VortexGizmo},
+	wall::{FenceGizmo, WallGizmo},
 };
 
-pub use self::emitter::ParticleLimit;
+pub use self::{
+	emitter::ParticleLimit, inspector::HoveredGizmo, motion::Motion, toggling::toggle_gizmos,
+};
 
 mod attractor;
+mod converter;
 mod deleter;
+mod dragging;
 mod eater;
 mod emitter;
+mod inspector;
+mod motion;
+mod overlay;
+mod portal;
 mod pusher;
+mod sensor;
+mod toggling;
+mod vortex;
+mod wall;
 
 pub struct GizmoPlugin;
 
 impl Plugin for GizmoPlugin {
 	fn build(&self, app: &mut App) {
-		app.add_systems(Update, (spawn_or_despawn_gizmos, adjust_particle_limit))
-			.add_systems(
-				FixedUpdate,
-				(
-					(
-						activate_attractors,
-						eaters_chasing_particles,
-						activate_pushers,
-					)
-						.before(merge_speed),
-					(
-						(activate_deleters, recharge_slow_deleters),
-						activate_slow_deleters,
-					)
-						.chain(),
-					activate_emitters,
-					activate_eaters,
-					apply_eater_scale,
-					process_dormant_eaters,
-				),
-			)
-			.init_resource::<ParticleLimit>();
+		app.init_resource::<GizmoRegistry>()
+			.add_plugins((
+				InspectorPlugin,
+				DraggingPlugin,
+				OverlayPlugin,
+				MotionPlugin,
+				TogglingPlugin,
+			))
+			.add_systems(Update, (spawn_or_despawn_gizmos, adjust_particle_limit))
+			.init_resource::<ParticleLimit>()
+			.register_gizmo(EmitterGizmo)
+			.register_gizmo(DirectionalEmitterGizmo)
+			.register_gizmo(BurstEmitterGizmo)
+			.register_gizmo(AreaEmitterGizmo)
+			.register_gizmo(DeleterGizmo)
+			.register_gizmo(SlowDeleterGizmo)
+			.register_gizmo(SelectiveDeleterGizmo)
+			.register_gizmo(SelectiveSlowDeleterGizmo)
+			.register_gizmo(AttractorGizmo)
+			.register_gizmo(RepulsorGizmo)
+			.register_gizmo(SelectiveAttractorGizmo)
+			.register_gizmo(ChargedPoleGizmo)
+			.register_gizmo(PusherGizmo)
+			.register_gizmo(EaterGizmo)
+			.register_gizmo(SwarmEaterGizmo)
+			.register_gizmo(VortexGizmo)
+			.register_gizmo(ClockwiseVortexGizmo)
+			.register_gizmo(WallGizmo)
+			.register_gizmo(FenceGizmo)
+			.register_gizmo(PortalGizmo)
+			.register_gizmo(ConverterGizmo)
+			.register_gizmo(SensorGizmo)
+			.register_gizmo(ChargeSensorGizmo);
+	}
+}
+
+/// Describes a kind of gizmo: how it is spawned and deleted, what it looks like, and which systems make it work. Add one to the app with [`RegisterGizmo::register_gizmo`].
+pub trait GizmoDefinition: Send + Sync + 'static {
+	/// Identifies this kind of gizmo. Must be unique among registered gizmos.
+	fn gizmo_type(&self) -> GizmoType;
+	/// The default keybinds and draw properties of each variant.
+	fn variants(&self) -> GizmoVariants;
+	/// Whether the gizmo gets [`Movement`], so it can be moved by forces.
+	fn has_movement(&self) -> bool {
+		false
+	}
+	fn placement_style(&self) -> PlacementStyle {
+		PlacementStyle::Instant
+	}
+	/// Whether right-dragging the gizmo turns it. By default, only gizmos placed with rotation can be turned.
+	fn can_turn(&self) -> bool {
+		self.placement_style() == PlacementStyle::WithRotation
+	}
+	/// Inserts the components that make an entity this kind of gizmo, besides the ones every gizmo gets.
+	fn insert_components(&self, entity_commands: &mut EntityCommands);
+	/// Called every frame while a [`PlacementStyle::WithRotation`] gizmo is being placed, with the offset from where it was placed to the cursor. Rotation is already taken care of.
+	fn drag(&self, _entity_commands: &mut EntityCommands, _offset: Vec2) {}
+	/// The component whose fields are shown in the inspector, and which of them scrolling over the gizmo adjusts.
+	fn parameters(&self) -> Option<GizmoParameters> {
+		None
+	}
+	/// Draws the region this gizmo affects, while the effect overlay is on.
+	fn draw_effect_area(&self, _entity: &EntityRef, _painter: &mut EffectPainter) {}
+	/// Adds the systems that make this kind of gizmo work. Called once, on registration.
+	fn build(&self, _app: &mut App) {}
+}
+
+pub trait RegisterGizmo {
+	/// Adds a kind of gizmo, along with its systems and keybinds. Keybinds are only set up for gizmos registered before [`Startup`].
+	fn register_gizmo(&mut self, definition: impl GizmoDefinition) -> &mut Self;
+}
+
+impl RegisterGizmo for App {
+	fn register_gizmo(&mut self, definition: impl GizmoDefinition) -> &mut Self {
+		definition.build(self);
+		let mut registry = self.world_mut().get_resource_or_init::<GizmoRegistry>();
+		assert!(
+			registry
+				.iter()
+				.all(|gizmo| gizmo.gizmo_type() != definition.gizmo_type()),
+			"Gizmo type {:?} was registered twice",
+			definition.gizmo_type()
+		);
+		registry.0.push(Box::new(definition));
+		self
+	}
+}
+
+/// Every kind of gizmo that can be spawned, in order of registration.
+#[derive(Resource, Default)]
+pub struct GizmoRegistry(Vec<Box<dyn GizmoDefinition>>);
+
+impl GizmoRegistry {
+	pub fn iter(&self) -> impl Iterator<Item = &dyn GizmoDefinition> {
+		self.0.iter().map(|gizmo| &**gizmo)
+	}
+	pub fn get(&self, gizmo_type: GizmoType) -> Option<&dyn GizmoDefinition> {
+		self.iter().find(|gizmo| gizmo.gizmo_type() == gizmo_type)
+	}
+}
+
+/// Gives access to the fields of a gizmo's parameter component that can be edited, through reflection, without knowing its type.
+#[derive(Clone, Copy)]
+pub struct GizmoParameters {
+	/// Paths to the editable fields. The first is adjusted by scrolling over the gizmo, unless another one is picked in the inspector.
+	fields: &'static [&'static str],
+	describe: fn(&EntityRef, &[&str]) -> Vec<(String, String)>,
+	scale: fn(&mut EntityCommands, String, f32),
+	snapshot: fn(&EntityRef) -> Option<Box<dyn PartialReflect>>,
+	restore: fn(&mut EntityWorldMut, &dyn PartialReflect),
+}
+
+impl GizmoParameters {
+	/// Only the given fields can be seen and edited, leaving out any state the gizmo keeps while running. Undoing still restores the whole component.
+	pub fn new<T: Component<Mutability = Mutable> + Reflect>(
+		fields: &'static [&'static str],
+	) -> Self {
+		Self {
+			fields,
+			describe: |entity, fields| {
+				entity.get::<T>().map_or_else(Vec::new, |component| {
+					fields
+						.iter()
+						.filter_map(|&field| {
+							let value = component.reflect_path(field).ok()?;
+							Some((field.to_owned(), describe_field(value)))
+						})
+						.collect()
+				})
+			},
+			scale: |entity_commands, field, factor| {
+				entity_commands
+					.entry::<T>()
+					.and_modify(move |mut component| {
+						if let Ok(field) = component.reflect_path_mut(field.as_str()) {
+							scale_field(field, factor);
+						}
+					});
+			},
+			snapshot: |entity| entity.get::<T>().map(|component| component.to_dynamic()),
+			restore: |entity, snapshot| {
+				if let Some(mut component) = entity.get_mut::<T>() {
+					component.apply(snapshot);
+				}
+			},
+		}
+	}
+	/// The field adjusted by scrolling over the gizmo, unless another one is picked in the inspector.
+	pub fn primary(&self) -> &'static str {
+		self.fields[0]
+	}
+	/// The path and value of every editable field the gizmo currently has, in the order they were given.
+	pub fn describe(&self, entity: &EntityRef) -> Vec<(String, String)> {
+		(self.describe)(entity, self.fields)
+	}
+	/// Multiplies the field by `factor`. Whole numbers always change by at least 1, and stay above 0. Booleans are switched on by a factor above 1, and off by one below. Optional numbers start at 1 when scaled up from unset, and are unset again when scaled down below 1. Enums without fields go to their next variant when scaled up, and their previous one when scaled down, looping around.
+	pub fn scale(&self, entity_commands: &mut EntityCommands, field: &str, factor: f32) {
+		(self.scale)(entity_commands, field.to_owned(), factor);
+	}
+	/// A copy of the whole parameter component, which can be put back with [`GizmoParameters::restore`].
+	pub fn snapshot(&self, entity: &EntityRef) -> Option<Box<dyn PartialReflect>> {
+		(self.snapshot)(entity)
+	}
+	pub fn restore(&self, entity: &mut EntityWorldMut, snapshot: &dyn PartialReflect) {
+		(self.restore)(entity, snapshot);
+	}
+}
+
+/// How a field is shown, given its path: `launch.0.speed` is shown as `launch speed`.
+pub fn field_label(path: &str) -> String {
+	path.split('.')
+		.filter(|part| part.parse::<usize>().is_err())
+		.collect::<Vec<_>>()
+		.join(" ")
+}
+
+fn describe_field(field: &dyn PartialReflect) -> String {
+	if let Some(value) = field.try_downcast_ref::<f32>() {
+		format!("{value:.2}")
+	} else if let Some(value) = field.try_downcast_ref::<Option<f32>>() {
+		value.map_or_else(|| String::from("unset"), |value| format!("{value:.2}"))
+	} else if let Some(value) = field.try_downcast_ref::<Option<u32>>() {
+		value.map_or_else(|| String::from("unset"), |value| value.to_string())
+	} else if let ReflectRef::Enum(value) = field.reflect_ref()
+		&& value.variant_type() == VariantType::Unit
+	{
+		value.variant_name().to_lowercase()
+	} else {
+		format!("{field:?}")
+	}
+}
+
+fn scale_field(field: &mut dyn PartialReflect, factor: f32) {
+	let scale_whole = |value: f32, max: f32| {
+		let scaled = (value * factor).round();
+		let scaled = if scaled != value {
+			scaled
+		} else if factor > 1.0 {
+			value + 1.0
+		} else {
+			value - 1.0
+		};
+		scaled.clamp(1.0, max)
+	};
+	if let Some(value) = field.try_downcast_mut::<f32>() {
+		*value *= factor;
+	} else if let Some(value) = field.try_downcast_mut::<u8>() {
+		*value = scale_whole(*value as f32, u8::MAX as f32) as u8;
+	} else if let Some(value) = field.try_downcast_mut::<u32>() {
+		*value = scale_whole(*value as f32, u32::MAX as f32) as u32;
+	} else if let Some(value) = field.try_downcast_mut::<bool>() {
+		*value = factor > 1.0;
+	} else if let Some(value) = field.try_downcast_mut::<Option<f32>>() {
+		*value = match *value {
+			None if factor > 1.0 => Some(1.0),
+			Some(current) if current * factor >= 1.0 => Some(current * factor),
+			_ => None,
+		};
+	} else if let Some(value) = field.try_downcast_mut::<Option<u32>>() {
+		*value = match *value {
+			None if factor > 1.0 => Some(1),
+			Some(1) if factor < 1.0 => None,
+			Some(current) => Some(scale_whole(current as f32, u32::MAX as f32) as u32),
+			None => None,
+		};
+	} else if let ReflectMut::Enum(value) = field.reflect_mut()
+		&& let Some(TypeInfo::Enum(info)) = value.get_represented_type_info()
+		&& info.iter().all(|variant| variant.as_unit_variant().is_ok())
+	{
+		let count = info.variant_len();
+		let index = if factor > 1.0 {
+			(value.variant_index() + 1) % count
+		} else {
+			(value.variant_index() + count - 1) % count
+		};
+		let variant = DynamicEnum::new(info.variant_names()[index], DynamicVariant::Unit);
+		value.apply(&variant);
 	}
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum PlacementStyle {
+pub enum PlacementStyle {
 	Instant,
 	WithRotation,
+	/// Every other placement completes a pair, linking both with [`LinkedGizmo`].
+	Paired,
 }
 
 #[derive(Component, Debug, Clone, Copy)]
 struct BeingPlaced;
 
-enum GizmoVariants {
+/// A [`PlacementStyle::Paired`] gizmo that was placed without a partner yet.
+#[derive(Component, Debug, Clone, Copy)]
+struct AwaitingPartner;
+
+/// A gizmo that is switched off, and does nothing until switched back on. Drawn dimmed.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Inactive;
+
+/// Makes a polar gizmo only affect particles of its own polarity, where gizmos without it affect both.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Selective;
+
+/// Whether a gizmo affects a particle, given whether the gizmo is [`Selective`] and whether each is [`Positive`].
+pub fn affects_particle(selective: bool, gizmo_positive: bool, particle_positive: bool) -> bool {
+	!selective || gizmo_positive == particle_positive
+}
+
+/// Another gizmo that belongs with this one. Deleting either deletes both.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct LinkedGizmo(pub Entity);
+
+pub enum GizmoVariants {
 	Neutral(GizmoVariant),
 	Polar {
 		negative: GizmoVariant,
@@ -76,188 +345,142 @@ enum GizmoVariants {
 	},
 }
 
-#[derive(Debug, Clone, Copy)]
-struct GizmoVariant {
-	action: Action,
-	draw_properties: DrawProperties,
+impl GizmoVariants {
+	/// Every variant, along with whether it is positive.
+	pub fn each(&self) -> impl Iterator<Item = (GizmoVariant, bool)> + use<> {
+		match *self {
+			Self::Neutral(variant) => [Some((variant, false)), None],
+			Self::Polar { negative, positive } => [Some((negative, false)), Some((positive, true))],
+		}
+		.into_iter()
+		.flatten()
+	}
 }
 
-struct Gizmo {
-	gizmo_type: GizmoType,
-	variants: GizmoVariants,
-	has_movement: bool,
-	placement_style: PlacementStyle,
+#[derive(Debug, Clone, Copy)]
+pub struct GizmoVariant {
+	pub key: KeyCode,
+	pub draw_properties: DrawProperties,
 }
 
-const GIZMOS: [Gizmo; 7] = [
-	Gizmo {
-		gizmo_type: GizmoType::Emitter,
-		variants: GizmoVariants::Polar {
-			negative: GizmoVariant {
-				action: Action::NegativeEmitter,
-				draw_properties: draw_properties::NEGATIVE_EMITTER,
-			},
-			positive: GizmoVariant {
-				action: Action::PositiveEmitter,
-				draw_properties: draw_properties::POSITIVE_EMITTER,
-			},
-		},
-		has_movement: false,
-		placement_style: PlacementStyle::Instant,
-	},
-	Gizmo {
-		gizmo_type: GizmoType::Deleter,
-		variants: GizmoVariants::Neutral(GizmoVariant {
-			action: Action::Deleter,
-			draw_properties: draw_properties::DELETER,
-		}),
-		has_movement: false,
-		placement_style: PlacementStyle::Instant,
-	},
-	Gizmo {
-		gizmo_type: GizmoType::SlowDeleter,
-		variants: GizmoVariants::Neutral(GizmoVariant {
-			action: Action::SlowDeleter,
-			draw_properties: draw_properties::SLOW_DELETER,
-		}),
-		has_movement: false,
-		placement_style: PlacementStyle::Instant,
-	},
-	Gizmo {
-		gizmo_type: GizmoType::Attractor,
-		variants: GizmoVariants::Neutral(GizmoVariant {
-			action: Action::Attractor,
-			draw_properties: draw_properties::ATTRACTOR,
-		}),
-		has_movement: false,
-		placement_style: PlacementStyle::Instant,
-	},
-	Gizmo {
-		gizmo_type: GizmoType::Repulsor,
-		variants: GizmoVariants::Neutral(GizmoVariant {
-			action: Action::Repulsor,
-			draw_properties: draw_properties::REPULSOR,
-		}),
-		has_movement: false,
-		placement_style: PlacementStyle::Instant,
-	},
-	Gizmo {
-		gizmo_type: GizmoType::Pusher,
-		variants: GizmoVariants::Neutral(GizmoVariant {
-			action: Action::Pusher,
-			draw_properties: draw_properties::PUSHER,
-		}),
-		has_movement: false,
-		placement_style: PlacementStyle::WithRotation,
-	},
-	Gizmo {
-		gizmo_type: GizmoType::Eater,
-		variants: GizmoVariants::Polar {
-			negative: GizmoVariant {
-				action: Action::NegativeEater,
-				draw_properties: draw_properties::NEGATIVE_EATER,
-			},
-			positive: GizmoVariant {
-				action: Action::PositiveEater,
-				draw_properties: draw_properties::POSITIVE_EATER,
-			},
-		},
-		has_movement: true,
-		placement_style: PlacementStyle::Instant,
-	},
-];
-
-#[derive(Clone, Copy, PartialEq, Component)]
-enum GizmoType {
-	Emitter,
-	Deleter,
-	SlowDeleter,
-	Attractor,
-	Repulsor,
-	Pusher,
-	Eater,
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub struct GizmoType(pub &'static str);
+
+/// The payload of [`Action::Gizmo`], identifying which gizmo variant to spawn or delete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub struct GizmoAction {
+	pub gizmo_type: GizmoType,
+	pub positive: bool,
 }
 
-impl GizmoType {
-	fn insert_using<'l, 'a>(
-		self,
-		entity_commands: &'l mut EntityCommands<'a>,
-	) -> &'l mut EntityCommands<'a> {
-		match self {
-			Self::Emitter => entity_commands.insert(Emitter::default()),
-			Self::Deleter => entity_commands.insert(Deleter::default()),
-			Self::SlowDeleter => entity_commands.insert(SlowDeleter::default()),
-			Self::Attractor => entity_commands.insert(Attractor::default()),
-			Self::Repulsor => entity_commands.insert(Attractor::repulsor()),
-			Self::Pusher => entity_commands.insert(Pusher),
-			Self::Eater => entity_commands.insert(Eater::default()),
-		}
-	}
+/// The gizmos [`spawn_or_despawn_gizmos`] may delete, link up or finish placing.
+#[derive(SystemParam)]
+struct ExistingGizmos<'w, 's> {
+	placed: Query<
+		'w,
+		's,
+		(
+			Entity,
+			&'static Transform,
+			&'static GizmoType,
+			Option<&'static Positive>,
+		),
+		Without<BeingPlaced>,
+	>,
+	placers: Query<'w, 's, (Entity, &'static mut Transform, &'static GizmoType), With<BeingPlaced>>,
+	awaiting_partner:
+		Query<'w, 's, (Entity, &'static GizmoType, Has<Positive>), With<AwaitingPartner>>,
+	links: Query<'w, 's, &'static LinkedGizmo>,
 }
 
 fn spawn_or_despawn_gizmos(
 	mut commands: Commands,
+	registry: Res<GizmoRegistry>,
 	texture_map: Res<TextureMap>,
-	window: Query<&Window, With<PrimaryWindow>>,
-	window_dimensions: Res<WindowDimensions>,
+	cursor: Cursor,
 	action_state: Query<&ActionState<Action>>,
-	gizmos: Query<(Entity, &Transform, &GizmoType, Option<&Positive>), Without<BeingPlaced>>,
-	mut placers: Query<(Entity, &mut Transform, &GizmoType), With<BeingPlaced>>,
+	mut existing: ExistingGizmos,
+	mut edits: EventWriter<Edit>,
 ) {
 	let action_state = action_state.single().unwrap();
-	let window = unwrap_or_return!(window.single().ok());
-	let cursor_pos = unwrap_or_return!(window.cursor_position());
-	let cursor_pos = Vec2::new(cursor_pos.x, window.height() - cursor_pos.y);
-
-	for gizmo in GIZMOS {
-		let variants = match gizmo.variants {
-			GizmoVariants::Neutral(variant) => [Some((variant, false)), None],
-			GizmoVariants::Polar { negative, positive } => {
-				[Some((negative, false)), Some((positive, true))]
+	let cursor_pos = unwrap_or_return!(cursor.position());
+
+	for gizmo in registry.iter() {
+		let gizmo_type = gizmo.gizmo_type();
+		for (variant, positive) in gizmo.variants().each() {
+			let action = Action::Gizmo(GizmoAction {
+				gizmo_type,
+				positive,
+			});
+			if action_state.just_pressed(&action) && action_state.pressed(&Action::ToggleModifier) {
+				// Switches gizmos of this variant on or off instead, in the toggling module.
+				continue;
 			}
-		};
-		for (variant, positive) in variants.into_iter().flatten() {
-			if action_state.just_pressed(&variant.action) {
+			if action_state.just_pressed(&action) {
 				if action_state.pressed(&Action::DespawnAllModifier) {
-					despawn_all_gizmos(&mut commands, &gizmo, gizmos, positive);
+					let deleted = despawn_all_gizmos(
+						&mut commands,
+						gizmo_type,
+						existing.placed,
+						existing.links,
+						positive,
+					);
+					if !deleted.is_empty() {
+						edits.write(Edit::Deleted(deleted));
+					}
 				} else if action_state.pressed(&Action::DespawnModifier) {
-					despawn_gizmo(
+					let deleted = despawn_gizmo(
 						&mut commands,
 						cursor_pos,
-						window_dimensions.0,
-						&gizmo,
-						gizmos,
+						cursor.window_dimensions(),
+						gizmo_type,
+						existing.placed,
+						existing.links,
 						positive,
 					);
+					if !deleted.is_empty() {
+						edits.write(Edit::Deleted(deleted));
+					}
 				} else {
-					let is_placer = gizmo.placement_style == PlacementStyle::WithRotation;
-					spawn_gizmo(
+					let is_placer = gizmo.placement_style() == PlacementStyle::WithRotation;
+					let entity = spawn_gizmo(
 						&mut commands,
 						&texture_map,
 						cursor_pos,
-						&gizmo,
+						gizmo,
 						&variant,
 						positive,
 						is_placer,
 					);
+					if gizmo.placement_style() == PlacementStyle::Paired {
+						link_or_await_partner(
+							&mut commands,
+							entity,
+							gizmo_type,
+							positive,
+							existing.awaiting_partner,
+						);
+					}
+					edits.write(Edit::Spawned(vec![entity]));
 				}
-			} else if gizmo.placement_style == PlacementStyle::WithRotation {
-				if action_state.pressed(&variant.action) {
-					for (_, mut transform, gizmo_type) in &mut placers {
-						if *gizmo_type == gizmo.gizmo_type {
+			} else if gizmo.placement_style() == PlacementStyle::WithRotation {
+				if action_state.pressed(&action) {
+					for (entity, mut transform, placer_type) in &mut existing.placers {
+						if *placer_type == gizmo_type {
 							let offset = cursor_pos - transform.translation.truncate();
 							transform.rotation = Quat::from_rotation_z(offset.to_angle());
+							gizmo.drag(&mut commands.entity(entity), offset);
 						}
 					}
-				} else if action_state.just_released(&variant.action) {
-					for (entity, _, gizmo_type) in &placers {
-						if *gizmo_type == gizmo.gizmo_type {
+				} else if action_state.just_released(&action) {
+					for (entity, _, placer_type) in &existing.placers {
+						if *placer_type == gizmo_type {
 							commands.entity(entity).remove::<BeingPlaced>();
 						}
 					}
 				} else {
-					for (entity, _, gizmo_type) in &placers {
-						if *gizmo_type == gizmo.gizmo_type {
+					for (entity, _, placer_type) in &existing.placers {
+						if *placer_type == gizmo_type {
 							commands.entity(entity).despawn();
 						}
 					}
@@ -267,15 +490,15 @@ fn spawn_or_despawn_gizmos(
 	}
 }
 
-fn spawn_gizmo<'a>(
-	commands: &'a mut Commands,
+fn spawn_gizmo(
+	commands: &mut Commands,
 	texture_map: &Res<TextureMap>,
 	position: Vec2,
-	gizmo: &'a Gizmo,
-	variant: &'a GizmoVariant,
+	gizmo: &dyn GizmoDefinition,
+	variant: &GizmoVariant,
 	positive: bool,
 	is_placer: bool,
-) {
+) -> Entity {
 	let DrawProperties {
 		draw_priority,
 		size,
@@ -299,10 +522,10 @@ fn spawn_gizmo<'a>(
 			scale: (Vec2::ONE * size).extend(1.0),
 			..default()
 		},
-		gizmo.gizmo_type,
+		gizmo.gizmo_type(),
 	));
 
-	gizmo.gizmo_type.insert_using(&mut entity_commands);
+	gizmo.insert_components(&mut entity_commands);
 
 	if is_placer {
 		entity_commands.insert(BeingPlaced);
@@ -310,45 +533,142 @@ fn spawn_gizmo<'a>(
 	if positive {
 		entity_commands.insert(Positive);
 	}
-	if gizmo.has_movement {
+	if gizmo.has_movement() {
 		entity_commands.insert(Movement::default());
 	}
+	entity_commands.id()
+}
+
+/// Links the new gizmo to an earlier one of the same variant that is still waiting for a partner, or else makes it wait for one.
+fn link_or_await_partner<'a>(
+	commands: &mut Commands,
+	entity: Entity,
+	gizmo_type: GizmoType,
+	positive: bool,
+	awaiting_partner: impl IntoIterator<Item = (Entity, &'a GizmoType, bool)>,
+) {
+	let partner = awaiting_partner
+		.into_iter()
+		.find_map(|(other, other_type, other_positive)| {
+			(*other_type == gizmo_type && other_positive == positive).then_some(other)
+		});
+	if let Some(partner) = partner {
+		commands
+			.entity(partner)
+			.remove::<AwaitingPartner>()
+			.insert(LinkedGizmo(entity));
+		commands.entity(entity).insert(LinkedGizmo(partner));
+	} else {
+		commands.entity(entity).insert(AwaitingPartner);
+	}
 }
 
+/// Deletes the nearest gizmo of the variant, and its partner. They are disabled rather than despawned, so this can be undone. Returns what was deleted.
 fn despawn_gizmo<'a>(
 	commands: &mut Commands,
 	coordinates: Vec2,
 	window_dimensions: Vec2,
-	gizmo: &'a Gizmo,
+	gizmo_type: GizmoType,
 	gizmos: impl IntoIterator<Item = (Entity, &'a Transform, &'a GizmoType, Option<&'a Positive>)>,
+	links: Query<&LinkedGizmo>,
 	positive: bool,
-) {
+) -> Vec<Entity> {
+	let mut deleted = Vec::new();
 	if let Some(gizmo) = find_entity_by_cursor(
 		coordinates,
 		window_dimensions,
 		gizmos
 			.into_iter()
-			.filter_map(|(entity, transform, gizmo_type, positive_component)| {
-				(&gizmo.gizmo_type == gizmo_type && positive_component.is_some() == positive)
+			.filter_map(|(entity, transform, other_type, positive_component)| {
+				(*other_type == gizmo_type && positive_component.is_some() == positive)
 					.then_some((entity, transform))
 			}),
 	) {
-		commands.entity(gizmo).despawn();
+		deleted.push(gizmo);
+		if let Ok(link) = links.get(gizmo) {
+			// The partner may already be disabled, if placing it was undone.
+			if links.contains(link.0) {
+				deleted.push(link.0);
+			}
+		}
 	}
+	for &gizmo in &deleted {
+		commands.entity(gizmo).insert(Disabled);
+	}
+	deleted
 }
 
+/// Like [`despawn_gizmo`], for every gizmo of the variant.
 fn despawn_all_gizmos<'a>(
 	commands: &'a mut Commands,
-	gizmo: &'a Gizmo,
+	gizmo_type: GizmoType,
 	gizmos: impl IntoIterator<Item = (Entity, &'a Transform, &'a GizmoType, Option<&'a Positive>)>,
+	links: Query<&LinkedGizmo>,
 	positive: bool,
-) {
+) -> Vec<Entity> {
+	let mut to_despawn = HashSet::new();
 	for gizmo in gizmos
 		.into_iter()
-		.filter_map(|(entity, _, gizmo_type, positive_component)| {
-			(&gizmo.gizmo_type == gizmo_type && positive_component.is_some() == positive)
+		.filter_map(|(entity, _, other_type, positive_component)| {
+			(*other_type == gizmo_type && positive_component.is_some() == positive)
 				.then_some(entity)
 		}) {
-		commands.entity(gizmo).despawn();
+		to_despawn.insert(gizmo);
+		if let Ok(link) = links.get(gizmo)
+			&& links.contains(link.0)
+		{
+			to_despawn.insert(link.0);
+		}
+	}
+	for &gizmo in &to_despawn {
+		commands.entity(gizmo).insert(Disabled);
+	}
+	to_despawn.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use emitter::{Emitter, EmitterShape, Launch};
+
+	#[test]
+	fn nested_fields_are_reached_by_path() {
+		let mut emitter = Emitter::new()
+			.with_shape(EmitterShape::Ring { radius: 20.0 })
+			.with_launch(Launch {
+				speed: 100.0,
+				spread: 0.5,
+			});
+		for path in ["shape.radius", "launch.0.speed"] {
+			scale_field(emitter.reflect_path_mut(path).unwrap(), 2.0);
+		}
+		assert_eq!(emitter.path::<f32>("shape.radius"), Ok(&40.0));
+		assert_eq!(emitter.path::<f32>("launch.0.speed"), Ok(&200.0));
+		assert_eq!(field_label("launch.0.speed"), "launch speed");
+	}
+
+	#[test]
+	fn optional_counts_are_set_and_unset_by_scaling() {
+		let mut budget: Option<u32> = None;
+		scale_field(&mut budget, 0.9);
+		assert_eq!(budget, None);
+		scale_field(&mut budget, 1.1);
+		assert_eq!(budget, Some(1));
+		scale_field(&mut budget, 1.1);
+		assert_eq!(budget, Some(2));
+		scale_field(&mut budget, 0.9);
+		scale_field(&mut budget, 0.9);
+		assert_eq!(budget, None);
+	}
+
+	#[test]
+	fn optional_amounts_are_unset_below_one() {
+		let mut lifetime: Option<f32> = None;
+		scale_field(&mut lifetime, 2.0);
+		assert_eq!(lifetime, Some(1.0));
+		scale_field(&mut lifetime, 2.0);
+		assert_eq!(lifetime, Some(2.0));
+		scale_field(&mut lifetime, 0.4);
+		assert_eq!(lifetime, None);
 	}
 }
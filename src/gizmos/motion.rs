@@ -0,0 +1,297 @@
+use std::f32::consts::TAU;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+	TIMESTEP, WindowDimensions,
+	common::Cursor,
+	gizmos::{BeingPlaced, GizmoRegistry, GizmoType, HoveredGizmo},
+	history::{Edit, GizmoState},
+	input::Action,
+	unwrap_or_return,
+};
+
+pub struct MotionPlugin;
+
+impl Plugin for MotionPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<PathDrawing>()
+			.add_systems(Update, (set_motion, draw_motions))
+			.add_systems(FixedUpdate, move_gizmos);
+	}
+}
+
+const ORBIT_RADIUS: f32 = 100.0;
+/// In radians/second.
+const ORBIT_SPEED: f32 = 1.0;
+/// How far an oscillating gizmo moves to either side of the middle.
+const OSCILLATION_AMPLITUDE: f32 = 150.0;
+/// In seconds.
+const OSCILLATION_PERIOD: f32 = 4.0;
+/// In units/second.
+const DRIFT_SPEED: f32 = 40.0;
+/// In units/second.
+const PATH_SPEED: f32 = 80.0;
+/// While drawing a path, the cursor has to move this far from the last point to add another.
+const PATH_POINT_SPACING: f32 = 10.0;
+/// Drawn paths shorter than this count as a tap instead, which cycles the motion.
+const MIN_PATH_LENGTH: f32 = 40.0;
+const MOTION_COLOR: Color = Color::srgba(0.6, 0.9, 0.6, 0.4);
+
+/// Moves a gizmo on its own. Motions only ever move a gizmo relative to where it is, so dragging it moves its whole path.
+#[derive(Component, Debug, Clone)]
+pub enum Motion {
+	/// Follows a closed polyline, given as offsets from its first point.
+	Path {
+		points: Vec<Vec2>,
+		/// In units/second.
+		speed: f32,
+		/// How far along the path it is.
+		travelled: f32,
+	},
+	/// Circles around a point.
+	Orbit {
+		radius: f32,
+		/// In radians/second.
+		angular_speed: f32,
+		angle: f32,
+	},
+	/// Moves back and forth along a line, easing in and out at the ends.
+	Oscillate {
+		/// The offset from the middle to one end.
+		extent: Vec2,
+		/// In seconds.
+		period: f32,
+		/// How far through a period it is, from 0 to 1.
+		phase: f32,
+	},
+	/// Moves in a straight line forever, wrapping around the edges of the screen.
+	Drift {
+		/// In units/second.
+		velocity: Vec2,
+	},
+}
+
+impl Motion {
+	/// The motion after `current` in the cycle tapping the motion key goes through, facing `direction` where that matters.
+	fn cycle(current: Option<&Self>, direction: Vec2) -> Option<Self> {
+		match current {
+			None => Some(Self::Orbit {
+				radius: ORBIT_RADIUS,
+				angular_speed: ORBIT_SPEED,
+				angle: 0.0,
+			}),
+			Some(Self::Orbit { .. }) => Some(Self::Oscillate {
+				extent: direction * OSCILLATION_AMPLITUDE,
+				period: OSCILLATION_PERIOD,
+				phase: 0.0,
+			}),
+			Some(Self::Oscillate { .. }) => Some(Self::Drift {
+				velocity: direction * DRIFT_SPEED,
+			}),
+			Some(Self::Drift { .. } | Self::Path { .. }) => None,
+		}
+	}
+	/// The offset from the point the motion is anchored to: the start of a path, the centre of an orbit or the middle of an oscillation.
+	fn offset(&self) -> Vec2 {
+		match self {
+			Self::Path {
+				points, travelled, ..
+			} => point_along_path(points, *travelled),
+			Self::Orbit { radius, angle, .. } => Vec2::from_angle(*angle) * *radius,
+			Self::Oscillate { extent, phase, .. } => *extent * (*phase * TAU).sin(),
+			Self::Drift { .. } => Vec2::ZERO,
+		}
+	}
+	/// Advances the motion by `time` seconds, returning how far it moved.
+	fn step(&mut self, time: f32) -> Vec2 {
+		let before = self.offset();
+		match self {
+			Self::Path {
+				speed, travelled, ..
+			} => *travelled += *speed * time,
+			Self::Orbit {
+				angular_speed,
+				angle,
+				..
+			} => *angle = (*angle + *angular_speed * time) % TAU,
+			Self::Oscillate { period, phase, .. } => *phase = (*phase + time / *period).fract(),
+			Self::Drift { velocity } => return *velocity * time,
+		}
+		self.offset() - before
+	}
+}
+
+/// The point `distance` along the closed polyline through `points`, looping around.
+fn point_along_path(points: &[Vec2], distance: f32) -> Vec2 {
+	let segments = || points.iter().zip(points.iter().cycle().skip(1));
+	let length: f32 = segments().map(|(start, end)| start.distance(*end)).sum();
+	if length == 0.0 {
+		return points.first().copied().unwrap_or_default();
+	}
+	let mut remaining = distance.rem_euclid(length);
+	for (start, end) in segments() {
+		let segment_length = start.distance(*end);
+		if remaining <= segment_length {
+			return start.lerp(*end, remaining / segment_length);
+		}
+		remaining -= segment_length;
+	}
+	points[0]
+}
+
+/// The gizmo a path is being drawn for, and the points drawn so far.
+#[derive(Resource, Default)]
+struct PathDrawing(Option<(Entity, Vec<Vec2>)>);
+
+/// What [`set_motion`] needs to start, continue or finish drawing a path.
+#[derive(SystemParam)]
+struct PathInput<'w, 's> {
+	cursor: Cursor<'w, 's>,
+	hovered: Res<'w, HoveredGizmo>,
+	drawing: ResMut<'w, PathDrawing>,
+}
+
+/// Holding the motion key over a gizmo and moving the cursor draws a path for it to follow. Tapping it instead cycles between orbiting, oscillating, drifting and standing still.
+fn set_motion(
+	mut commands: Commands,
+	mut gizmos: Gizmos,
+	registry: Res<GizmoRegistry>,
+	action_state: Query<&ActionState<Action>>,
+	mut input: PathInput,
+	entities: Query<EntityRef, With<GizmoType>>,
+	mut edits: EventWriter<Edit>,
+) {
+	let action_state = action_state.single().unwrap();
+	let cursor_pos = unwrap_or_return!(input.cursor.position());
+
+	if action_state.just_pressed(&Action::SetMotion) {
+		input.drawing.0 = input.hovered.0.map(|entity| (entity, vec![cursor_pos]));
+	}
+	let Some((entity, points)) = &mut input.drawing.0 else {
+		return;
+	};
+	if action_state.pressed(&Action::SetMotion) {
+		if points.last().unwrap().distance(cursor_pos) >= PATH_POINT_SPACING {
+			points.push(cursor_pos);
+		}
+		gizmos.linestrip_2d(points.iter().copied(), MOTION_COLOR);
+		return;
+	}
+
+	let (entity, points) = (*entity, std::mem::take(points));
+	input.drawing.0 = None;
+	let Ok(gizmo) = entities.get(entity) else {
+		return;
+	};
+	let length: f32 = points
+		.windows(2)
+		.map(|pair| pair[0].distance(pair[1]))
+		.sum();
+	let motion = if length >= MIN_PATH_LENGTH {
+		let start = points[0];
+		Some(Motion::Path {
+			points: points.into_iter().map(|point| point - start).collect(),
+			speed: PATH_SPEED,
+			travelled: 0.0,
+		})
+	} else {
+		let rotation = gizmo.get::<Transform>().unwrap().rotation;
+		Motion::cycle(gizmo.get::<Motion>(), (rotation * Vec3::X).truncate())
+	};
+
+	let parameters = registry
+		.get(*gizmo.get::<GizmoType>().unwrap())
+		.and_then(|gizmo| gizmo.parameters());
+	edits.write(Edit::Changed {
+		entity,
+		before: GizmoState::capture(&gizmo, parameters),
+	});
+	match motion {
+		Some(motion) => commands.entity(entity).insert(motion),
+		None => commands.entity(entity).remove::<Motion>(),
+	};
+}
+
+fn move_gizmos(
+	window_dimensions: Res<WindowDimensions>,
+	mut gizmos: Query<(&mut Motion, &mut Transform), Without<BeingPlaced>>,
+) {
+	for (mut motion, mut transform) in &mut gizmos {
+		let position = (transform.translation.truncate() + motion.step(TIMESTEP))
+			.rem_euclid(window_dimensions.0);
+		transform.translation.x = position.x;
+		transform.translation.y = position.y;
+	}
+}
+
+/// Draws the path, orbit or line each moving gizmo follows, or the direction it drifts in.
+fn draw_motions(mut gizmos: Gizmos, moving: Query<(&Motion, &Transform)>) {
+	for (motion, transform) in &moving {
+		let position = transform.translation.truncate();
+		let anchor = position - motion.offset();
+		match motion {
+			Motion::Path { points, .. } => {
+				gizmos.linestrip_2d(
+					points
+						.iter()
+						.chain(points.first())
+						.map(|point| anchor + *point),
+					MOTION_COLOR,
+				);
+			}
+			Motion::Orbit { radius, .. } => {
+				gizmos.circle_2d(anchor, *radius, MOTION_COLOR);
+			}
+			Motion::Oscillate { extent, .. } => {
+				gizmos.line_2d(anchor - *extent, anchor + *extent, MOTION_COLOR);
+			}
+			Motion::Drift { velocity } => {
+				gizmos.arrow_2d(position, position + *velocity, MOTION_COLOR);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn assert_near(actual: Vec2, expected: Vec2) {
+		assert!(
+			actual.distance(expected) < 0.001,
+			"expected {expected}, got {actual}"
+		);
+	}
+
+	#[test]
+	fn path_is_followed_and_closed() {
+		let square = [
+			Vec2::ZERO,
+			Vec2::new(10.0, 0.0),
+			Vec2::new(10.0, 10.0),
+			Vec2::new(0.0, 10.0),
+		];
+		assert_near(point_along_path(&square, 0.0), Vec2::ZERO);
+		assert_near(point_along_path(&square, 5.0), Vec2::new(5.0, 0.0));
+		assert_near(point_along_path(&square, 15.0), Vec2::new(10.0, 5.0));
+		// The last stretch leads back to the start.
+		assert_near(point_along_path(&square, 35.0), Vec2::new(0.0, 5.0));
+		assert_near(point_along_path(&square, 40.0), Vec2::ZERO);
+	}
+
+	#[test]
+	fn path_loops_around() {
+		let line = [Vec2::ZERO, Vec2::new(10.0, 0.0)];
+		assert_near(point_along_path(&line, 25.0), Vec2::new(5.0, 0.0));
+		assert_near(point_along_path(&line, -5.0), Vec2::new(5.0, 0.0));
+	}
+
+	#[test]
+	fn degenerate_paths_stay_put() {
+		assert_near(point_along_path(&[], 10.0), Vec2::ZERO);
+		let point = [Vec2::new(3.0, 4.0), Vec2::new(3.0, 4.0)];
+		assert_near(point_along_path(&point, 10.0), Vec2::new(3.0, 4.0));
+	}
+}
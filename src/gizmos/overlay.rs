@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+	WindowDimensions,
+	gizmos::{GizmoRegistry, GizmoType},
+	input::Action,
+};
+
+pub struct OverlayPlugin;
+
+impl Plugin for OverlayPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<EffectOverlay>()
+			.add_systems(Update, (toggle_effect_overlay, draw_effect_areas).chain());
+	}
+}
+
+pub const EFFECT_AREA_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.25);
+pub const PROGRESS_COLOR: Color = Color::srgba(1.0, 0.8, 0.3, 0.8);
+pub const DORMANCY_COLOR: Color = Color::srgba(0.5, 0.5, 0.5, 0.8);
+
+/// Whether the regions gizmos affect are drawn.
+#[derive(Resource, Default)]
+struct EffectOverlay(bool);
+
+/// Draws shapes relative to a gizmo, rotated along with it. Shapes that cross an edge of the window are also drawn where they wrap around to.
+pub struct EffectPainter<'a, 'w, 's> {
+	gizmos: &'a mut Gizmos<'w, 's>,
+	dimensions: Vec2,
+	isometry: Isometry2d,
+}
+
+impl EffectPainter<'_, '_, '_> {
+	pub fn circle(&mut self, radius: f32, color: Color) {
+		for isometry in self.wrapped(Vec2::splat(radius)) {
+			self.gizmos.circle_2d(isometry, radius, color);
+		}
+	}
+	/// A rectangle of the given size, centered on the gizmo.
+	pub fn rect(&mut self, size: Vec2, color: Color) {
+		for isometry in self.wrapped(Vec2::splat(size.length() / 2.0)) {
+			self.gizmos.rect_2d(isometry, size, color);
+		}
+	}
+	/// An arc covering `fraction` of a circle, centered on the gizmo's up direction.
+	pub fn arc(&mut self, radius: f32, fraction: f32, color: Color) {
+		for isometry in self.wrapped(Vec2::splat(radius)) {
+			self.gizmos
+				.arc_2d(isometry, fraction * std::f32::consts::TAU, radius, color);
+		}
+	}
+	/// A line between two points relative to the gizmo.
+	pub fn line(&mut self, start: Vec2, end: Vec2, color: Color) {
+		let extent = start.length().max(end.length());
+		for isometry in self.wrapped(Vec2::splat(extent)) {
+			self.gizmos.line_2d(
+				isometry.transform_point(start),
+				isometry.transform_point(end),
+				color,
+			);
+		}
+	}
+	/// The gizmo's isometry, plus copies shifted by the window size for every edge within `extent` of the gizmo.
+	fn wrapped(&self, extent: Vec2) -> Vec<Isometry2d> {
+		let position = self.isometry.translation;
+		let shifts = |position: f32, extent: f32, size: f32| {
+			[
+				Some(0.0),
+				(position - extent < 0.0).then_some(size),
+				(position + extent > size).then_some(-size),
+			]
+			.into_iter()
+			.flatten()
+		};
+		shifts(position.x, extent.x, self.dimensions.x)
+			.flat_map(|x| {
+				shifts(position.y, extent.y, self.dimensions.y).map(move |y| Vec2::new(x, y))
+			})
+			.map(|shift| Isometry2d::new(position + shift, self.isometry.rotation))
+			.collect()
+	}
+}
+
+fn toggle_effect_overlay(
+	mut overlay: ResMut<EffectOverlay>,
+	action_state: Query<&ActionState<Action>>,
+) {
+	if action_state
+		.single()
+		.unwrap()
+		.just_pressed(&Action::ToggleEffectOverlay)
+	{
+		overlay.0 = !overlay.0;
+	}
+}
+
+fn draw_effect_areas(
+	mut gizmos: Gizmos,
+	overlay: Res<EffectOverlay>,
+	registry: Res<GizmoRegistry>,
+	window_dimensions: Res<WindowDimensions>,
+	entities: Query<EntityRef, With<GizmoType>>,
+) {
+	if !overlay.0 {
+		return;
+	}
+	for entity in &entities {
+		let (Some(gizmo_type), Some(transform)) =
+			(entity.get::<GizmoType>(), entity.get::<Transform>())
+		else {
+			continue;
+		};
+		let Some(definition) = registry.get(*gizmo_type) else {
+			continue;
+		};
+		let mut painter = EffectPainter {
+			gizmos: &mut gizmos,
+			dimensions: window_dimensions.0,
+			isometry: Isometry2d::new(
+				transform.translation.truncate(),
+				Rot2::radians((transform.rotation * Vec3::X).truncate().to_angle()),
+			),
+		};
+		definition.draw_effect_area(&entity, &mut painter);
+	}
+}
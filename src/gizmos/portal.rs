@@ -0,0 +1,150 @@
+use bevy::{ecs::system::EntityCommands, prelude::*};
+
+use crate::{
+	WindowDimensions,
+	common::wrapping_offset_2d,
+	draw_properties,
+	gizmos::{
+		AwaitingPartner, EffectPainter, GizmoDefinition, GizmoParameters, GizmoType, GizmoVariant,
+		GizmoVariants, Inactive, LinkedGizmo, PlacementStyle, overlay::EFFECT_AREA_COLOR,
+	},
+	movement::{Movement, apply_movement, clamp_speed},
+	particle::Particle,
+};
+
+pub struct PortalGizmo;
+
+impl GizmoDefinition for PortalGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Portal")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Neutral(GizmoVariant {
+			key: KeyCode::KeyO,
+			draw_properties: draw_properties::PORTAL,
+		})
+	}
+	fn placement_style(&self) -> PlacementStyle {
+		PlacementStyle::Paired
+	}
+	fn can_turn(&self) -> bool {
+		true
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert(Portal::default());
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Portal>(&["radius"]))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(portal) = entity.get::<Portal>() {
+			painter.circle(portal.radius, EFFECT_AREA_COLOR);
+		}
+	}
+	fn build(&self, app: &mut App) {
+		app.add_systems(Update, draw_portal_links).add_systems(
+			FixedUpdate,
+			teleport_through_portals
+				.after(clamp_speed)
+				.before(apply_movement),
+		);
+	}
+}
+
+const PORTAL_LINK_COLOR: Color = Color::srgba(1.0, 0.6, 0.0, 0.15);
+
+/// Moves particles that enter its radius to its [`LinkedGizmo`], keeping their offset from the centre. Their offset and movement are turned by however much more the partner is turned than this portal.
+#[derive(Component, Reflect)]
+pub struct Portal {
+	radius: f32,
+}
+
+impl Default for Portal {
+	fn default() -> Self {
+		Self { radius: 20.0 }
+	}
+}
+
+/// A particle that came out of a portal and has not left its radius yet. Without this, it would go right back.
+#[derive(Component)]
+pub struct InPortal;
+
+fn teleport_through_portals(
+	mut commands: Commands,
+	window_dimensions: Res<WindowDimensions>,
+	portals: Query<
+		(&Portal, &Transform, &LinkedGizmo),
+		(
+			Without<AwaitingPartner>,
+			Without<Inactive>,
+			Without<Particle>,
+		),
+	>,
+	mut particles: Query<
+		(Entity, &mut Transform, Option<&mut Movement>, Has<InPortal>),
+		With<Particle>,
+	>,
+) {
+	for (entity, mut transform, movement, in_portal) in &mut particles {
+		let position = transform.translation.truncate();
+		let entered = portals.iter().find(|(portal, portal_transform, _)| {
+			wrapping_offset_2d(
+				position,
+				portal_transform.translation.truncate(),
+				window_dimensions.0,
+			)
+			.length_squared()
+				< portal.radius.powi(2)
+		});
+		let Some((_, portal_transform, link)) = entered else {
+			if in_portal {
+				commands.entity(entity).try_remove::<InPortal>();
+			}
+			continue;
+		};
+		if in_portal {
+			continue;
+		}
+		let Ok((_, partner_transform, _)) = portals.get(link.0) else {
+			continue;
+		};
+
+		let facing = |transform: &Transform| (transform.rotation * Vec3::X).truncate();
+		let rotation = Rot2::radians(facing(portal_transform).angle_to(facing(partner_transform)));
+		let offset = wrapping_offset_2d(
+			position,
+			portal_transform.translation.truncate(),
+			window_dimensions.0,
+		);
+		let destination = (partner_transform.translation.truncate() + rotation * offset)
+			.rem_euclid(window_dimensions.0);
+		transform.translation.x = destination.x;
+		transform.translation.y = destination.y;
+		// This runs before movement is applied, so even without inertia, the turned movement is what moves it out of the partner.
+		if let Some(mut movement) = movement {
+			let turned = rotation * movement.get();
+			movement.set(turned);
+		}
+		// It may have been despawned this tick.
+		commands.entity(entity).try_insert(InPortal);
+	}
+}
+
+fn draw_portal_links(
+	mut gizmos: Gizmos,
+	portals: Query<(Entity, &Transform, &LinkedGizmo), With<Portal>>,
+) {
+	for (entity, transform, link) in &portals {
+		// Each pair only needs one line.
+		if entity > link.0 {
+			continue;
+		}
+		if let Ok((_, partner_transform, _)) = portals.get(link.0) {
+			gizmos.line_2d(
+				transform.translation.truncate(),
+				partner_transform.translation.truncate(),
+				PORTAL_LINK_COLOR,
+			);
+		}
+	}
+}
@@ -1,34 +1,167 @@
-use bevy::prelude::*;
+use bevy::{ecs::system::EntityCommands, prelude::*};
 
 use crate::{
 	WindowDimensions,
 	common::wrapping_offset_2d,
-	gizmos::BeingPlaced,
-	movement::{Movement, MovementTrait},
+	draw_properties,
+	gizmos::{
+		BeingPlaced, EffectPainter, GizmoDefinition, GizmoParameters, GizmoType, GizmoVariant,
+		GizmoVariants, Inactive, PlacementStyle, overlay::EFFECT_AREA_COLOR,
+	},
+	movement::{Movement, MovementTrait, merge_speed},
 	particle::Particle,
 };
 
-#[derive(Component, Debug, Clone, Copy, Default)]
-pub(super) struct Pusher;
+pub struct PusherGizmo;
 
-pub(super) fn activate_pushers(
+impl GizmoDefinition for PusherGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Pusher")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Neutral(GizmoVariant {
+			key: KeyCode::Period,
+			draw_properties: draw_properties::PUSHER,
+		})
+	}
+	fn placement_style(&self) -> PlacementStyle {
+		PlacementStyle::WithRotation
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert(Pusher::default());
+	}
+	fn drag(&self, entity_commands: &mut EntityCommands, offset: Vec2) {
+		drag_pusher(entity_commands, offset);
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Pusher>(&[
+			"strength", "width", "height", "falloff",
+		]))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(pusher) = entity.get::<Pusher>() {
+			painter.rect(pusher.size(), EFFECT_AREA_COLOR);
+		}
+	}
+	fn build(&self, app: &mut App) {
+		app.add_systems(FixedUpdate, activate_pushers.before(merge_speed));
+	}
+}
+
+/// Dragging while placing a pusher can't make its region shorter than this.
+const MIN_PUSHER_WIDTH: f32 = 40.0;
+/// The standard deviation of [`Falloff::Gaussian`], as a fraction of the distance from the centre to the edge.
+const GAUSSIAN_DEVIATION: f32 = 0.4;
+
+/// Pushes particles within a rectangle centred on it in the direction it faces.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+pub(super) struct Pusher {
+	/// The size of the region along the direction it pushes.
+	width: f32,
+	/// The size of the region across the direction it pushes.
+	height: f32,
+	/// How far particles in the middle of the region are pushed every tick.
+	strength: f32,
+	falloff: Falloff,
+}
+
+impl Pusher {
+	fn size(&self) -> Vec2 {
+		Vec2::new(self.width, self.height)
+	}
+	/// The movement per tick this gives something at `position`, if it is inside the region.
+	pub(super) fn push(&self, transform: &Transform, position: Vec2, wrap: Vec2) -> Option<Vec2> {
+		let offset = wrapping_offset_2d(transform.translation.truncate(), position, wrap);
+		let local_point = (transform.rotation.inverse() * offset.extend(0.0)).truncate();
+		self.push_at(local_point)
+			.map(|push| (transform.rotation * Vec3::X).truncate() * push)
+	}
+	/// How far a particle at `local_point`, relative to the pusher and unrotated, is pushed, if it is inside the region.
+	fn push_at(&self, local_point: Vec2) -> Option<f32> {
+		let relative = local_point.abs() / (self.size() / 2.0);
+		if !relative.cmple(Vec2::ONE).all() {
+			return None;
+		}
+		let distance = relative.max_element();
+		let factor = match self.falloff {
+			Falloff::Uniform => 1.0,
+			Falloff::Linear => 1.0 - distance,
+			Falloff::Gaussian => (-distance.powi(2) / (2.0 * GAUSSIAN_DEVIATION.powi(2))).exp(),
+		};
+		Some(self.strength * factor)
+	}
+}
+
+impl Default for Pusher {
+	fn default() -> Self {
+		Self {
+			width: 400.0,
+			height: 200.0,
+			strength: 3.0,
+			falloff: Falloff::Uniform,
+		}
+	}
+}
+
+/// How a [`Pusher`]'s strength changes from the middle of its region to the edges.
+#[derive(Debug, Clone, Copy, Reflect)]
+pub enum Falloff {
+	Uniform,
+	/// Down to nothing at the edges.
+	Linear,
+	/// Along a bell curve, so most of the push is near the middle.
+	Gaussian,
+}
+
+fn drag_pusher(entity_commands: &mut EntityCommands, offset: Vec2) {
+	let width = (offset.length() * 2.0).max(MIN_PUSHER_WIDTH);
+	entity_commands
+		.entry::<Pusher>()
+		.and_modify(move |mut pusher| pusher.width = width);
+}
+
+fn activate_pushers(
 	window_dimensions: Res<WindowDimensions>,
-	pushers: Query<&Transform, (With<Pusher>, Without<BeingPlaced>)>,
+	pushers: Query<(&Pusher, &Transform), (Without<BeingPlaced>, Without<Inactive>)>,
 	particles: Query<(&mut Movement, &Transform), With<Particle>>,
 ) {
 	for (mut movement, particle_transform) in particles {
-		for pusher_transform in pushers {
-			let offset = wrapping_offset_2d(
-				pusher_transform.translation.truncate(),
+		for (pusher, pusher_transform) in pushers {
+			if let Some(push) = pusher.push(
+				pusher_transform,
 				particle_transform.translation.truncate(),
 				window_dimensions.0,
-			);
-			let _offset =
-				pusher_transform.translation.truncate() - particle_transform.translation.truncate();
-			let local_point = (pusher_transform.rotation.inverse() * offset.extend(0.0)).truncate();
-			if local_point.x.abs() <= 200.0 && local_point.y.abs() <= 100.0 {
-				movement.add((pusher_transform.rotation * Vec3::X).truncate() * 3.0);
+			) {
+				movement.add(push);
 			}
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::gizmos::scale_field;
+
+	#[test]
+	fn linear_falloff_weakens_towards_the_edge() {
+		let pusher = Pusher {
+			falloff: Falloff::Linear,
+			..default()
+		};
+		let centre = pusher.push_at(Vec2::ZERO).unwrap();
+		let near_edge = pusher.push_at(Vec2::new(pusher.width * 0.45, 0.0)).unwrap();
+		assert!(near_edge < centre);
+		assert!(near_edge > 0.0);
+	}
+
+	#[test]
+	fn falloff_cycles_when_scaled() {
+		let mut pusher = Pusher::default();
+		scale_field(&mut pusher.falloff, 1.1);
+		assert!(matches!(pusher.falloff, Falloff::Linear));
+		scale_field(&mut pusher.falloff, 0.9);
+		scale_field(&mut pusher.falloff, 0.9);
+		assert!(matches!(pusher.falloff, Falloff::Gaussian));
+	}
+}
@@ -0,0 +1,259 @@
+use bevy::{ecs::system::EntityCommands, prelude::*};
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+	WindowDimensions,
+	common::{Positive, wrapping_offset_2d},
+	draw_properties,
+	gizmos::{
+		BeingPlaced, EffectPainter, GizmoDefinition, GizmoParameters, GizmoType, GizmoVariant,
+		GizmoVariants, HoveredGizmo, Inactive, inspector::SelectedGizmo,
+		overlay::EFFECT_AREA_COLOR,
+	},
+	input::Action,
+	particle::Particle,
+};
+
+/// Measures how many particles are within its radius, switching the gizmos wired to it when that crosses a threshold.
+pub struct SensorGizmo;
+
+impl GizmoDefinition for SensorGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Sensor")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Neutral(GizmoVariant {
+			key: KeyCode::KeyQ,
+			draw_properties: draw_properties::SENSOR,
+		})
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert((Sensor::new(SensorMeasure::Count), Wires::default()));
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Sensor>(&[
+			"threshold",
+			"radius",
+			"on_below",
+		]))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(sensor) = entity.get::<Sensor>() {
+			painter.circle(sensor.radius, EFFECT_AREA_COLOR);
+		}
+	}
+	fn build(&self, app: &mut App) {
+		app.add_event::<SensorTriggered>()
+			.add_systems(Update, (wire_sensors, draw_wires).chain())
+			.add_systems(FixedUpdate, (read_sensors, switch_wired_gizmos).chain());
+	}
+}
+
+/// A [`Sensor`] measuring the number of positive particles within its radius minus the number of negative ones. Relies on [`SensorGizmo`] for its systems.
+pub struct ChargeSensorGizmo;
+
+impl GizmoDefinition for ChargeSensorGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Charge sensor")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Neutral(GizmoVariant {
+			key: KeyCode::Digit8,
+			draw_properties: draw_properties::CHARGE_SENSOR,
+		})
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert((Sensor::new(SensorMeasure::NetCharge), Wires::default()));
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Sensor>(&[
+			"threshold",
+			"radius",
+			"on_below",
+		]))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(sensor) = entity.get::<Sensor>() {
+			painter.circle(sensor.radius, EFFECT_AREA_COLOR);
+		}
+	}
+}
+
+const WIRE_COLOR: Color = Color::srgba(1.0, 1.0, 0.5, 0.5);
+const INACTIVE_WIRE_COLOR: Color = Color::srgba(0.5, 0.5, 0.5, 0.5);
+
+#[derive(Component, Reflect)]
+pub struct Sensor {
+	radius: f32,
+	measure: SensorMeasure,
+	/// The reading at which wired gizmos are switched.
+	threshold: f32,
+	/// Whether wired gizmos are on while the reading is below the threshold, rather than while it is at or above it.
+	on_below: bool,
+	/// The latest reading.
+	reading: f32,
+	/// Whether the reading was below the threshold when last checked.
+	below: bool,
+}
+
+impl Sensor {
+	fn new(measure: SensorMeasure) -> Self {
+		Self {
+			radius: 100.0,
+			measure,
+			threshold: match measure {
+				SensorMeasure::Count => 20.0,
+				SensorMeasure::NetCharge => 5.0,
+			},
+			on_below: true,
+			reading: 0.0,
+			below: true,
+		}
+	}
+	/// Whether the gizmos wired to this should be on.
+	fn switched_on(&self) -> bool {
+		self.below == self.on_below
+	}
+}
+
+/// What a [`Sensor`] reads.
+#[derive(Debug, Clone, Copy, Reflect)]
+pub enum SensorMeasure {
+	/// The number of particles.
+	Count,
+	/// The number of positive particles minus the number of negative ones.
+	NetCharge,
+}
+
+/// The gizmos a [`Sensor`] switches on and off.
+#[derive(Component, Debug, Default)]
+pub struct Wires(Vec<Entity>);
+
+/// Sent when a sensor's reading crosses its threshold.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SensorTriggered {
+	pub sensor: Entity,
+	/// Whether the reading went below the threshold, rather than up to it.
+	pub below: bool,
+}
+
+/// Switches a gizmo on or off. It may have been deleted.
+fn switch(commands: &mut Commands, entity: Entity, on: bool) {
+	if on {
+		commands.entity(entity).try_remove::<Inactive>();
+	} else {
+		commands.entity(entity).try_insert(Inactive);
+	}
+}
+
+fn read_sensors(
+	window_dimensions: Res<WindowDimensions>,
+	mut sensors: Query<
+		(Entity, &mut Sensor, &Transform),
+		(Without<BeingPlaced>, Without<Inactive>),
+	>,
+	particles: Query<(Has<Positive>, &Transform), With<Particle>>,
+	mut triggered: EventWriter<SensorTriggered>,
+) {
+	for (entity, mut sensor, sensor_transform) in &mut sensors {
+		let sensor_position = sensor_transform.translation.truncate();
+		let radius_squared = sensor.radius.powi(2);
+		let nearby = particles.iter().filter(|(_, transform)| {
+			wrapping_offset_2d(
+				transform.translation.truncate(),
+				sensor_position,
+				window_dimensions.0,
+			)
+			.length_squared()
+				< radius_squared
+		});
+		sensor.reading = match sensor.measure {
+			SensorMeasure::Count => nearby.count() as f32,
+			SensorMeasure::NetCharge => nearby
+				.map(|(positive, _)| if positive { 1.0 } else { -1.0 })
+				.sum(),
+		};
+		let below = sensor.reading < sensor.threshold;
+		if below != sensor.below {
+			sensor.below = below;
+			triggered.write(SensorTriggered {
+				sensor: entity,
+				below,
+			});
+		}
+	}
+}
+
+fn switch_wired_gizmos(
+	mut commands: Commands,
+	mut triggered: EventReader<SensorTriggered>,
+	sensors: Query<(&Sensor, &Wires)>,
+) {
+	for event in triggered.read() {
+		let Ok((sensor, wires)) = sensors.get(event.sensor) else {
+			continue;
+		};
+		let on = event.below == sensor.on_below;
+		for &target in &wires.0 {
+			switch(&mut commands, target, on);
+		}
+	}
+}
+
+/// With a sensor selected, pressing the wire key over another gizmo wires it to the sensor, or unwires it if it already was. Unwired gizmos are switched back on.
+fn wire_sensors(
+	mut commands: Commands,
+	action_state: Query<&ActionState<Action>>,
+	selected: Res<SelectedGizmo>,
+	hovered: Res<HoveredGizmo>,
+	mut sensors: Query<(&Sensor, &mut Wires)>,
+) {
+	if !action_state
+		.single()
+		.unwrap()
+		.just_pressed(&Action::WireSensor)
+	{
+		return;
+	}
+	let (Some(sensor_entity), Some(target)) = (selected.entity, hovered.0) else {
+		return;
+	};
+	if sensor_entity == target {
+		return;
+	}
+	let Ok((sensor, mut wires)) = sensors.get_mut(sensor_entity) else {
+		return;
+	};
+	if let Some(index) = wires.0.iter().position(|&wired| wired == target) {
+		wires.0.remove(index);
+		switch(&mut commands, target, true);
+	} else {
+		wires.0.push(target);
+		switch(&mut commands, target, sensor.switched_on());
+	}
+}
+
+fn draw_wires(
+	mut gizmos: Gizmos,
+	sensors: Query<(&Sensor, &Wires, &Transform)>,
+	targets: Query<&Transform, With<GizmoType>>,
+) {
+	for (sensor, wires, sensor_transform) in &sensors {
+		let color = if sensor.switched_on() {
+			WIRE_COLOR
+		} else {
+			INACTIVE_WIRE_COLOR
+		};
+		for target_transform in wires
+			.0
+			.iter()
+			.filter_map(|&target| targets.get(target).ok())
+		{
+			gizmos.line_2d(
+				sensor_transform.translation.truncate(),
+				target_transform.translation.truncate(),
+				color,
+			);
+		}
+	}
+}
@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+	common::Positive,
+	gizmos::{BeingPlaced, GizmoAction, GizmoRegistry, GizmoType, HoveredGizmo, Inactive},
+	history::Edit,
+	input::Action,
+};
+
+pub struct TogglingPlugin;
+
+impl Plugin for TogglingPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_systems(
+			Update,
+			(
+				toggle_clicked_gizmo,
+				toggle_gizmo_kinds,
+				dim_inactive_gizmos,
+			),
+		);
+	}
+}
+
+/// How opaque inactive gizmos are drawn.
+const INACTIVE_ALPHA: f32 = 0.3;
+
+/// Switches each gizmo off if it is on, or on if it is off.
+pub fn toggle_gizmos(world: &mut World, entities: &[Entity]) {
+	for &entity in entities {
+		let Ok(mut entity) = world.get_entity_mut(entity) else {
+			continue;
+		};
+		if entity.contains::<Inactive>() {
+			entity.remove::<Inactive>();
+		} else {
+			entity.insert(Inactive);
+		}
+	}
+}
+
+/// Clicking a gizmo while holding the toggle modifier switches it on or off.
+fn toggle_clicked_gizmo(
+	mut commands: Commands,
+	action_state: Query<&ActionState<Action>>,
+	hovered: Res<HoveredGizmo>,
+	mut edits: EventWriter<Edit>,
+) {
+	let action_state = action_state.single().unwrap();
+	if !action_state.just_pressed(&Action::SpawnParticle)
+		|| !action_state.pressed(&Action::ToggleModifier)
+	{
+		return;
+	}
+	if let Some(entity) = hovered.0 {
+		commands.queue(move |world: &mut World| toggle_gizmos(world, &[entity]));
+		edits.write(Edit::Toggled(vec![entity]));
+	}
+}
+
+/// Pressing a gizmo's button while holding the toggle modifier switches every gizmo of that variant off, or all of them back on if they already were.
+fn toggle_gizmo_kinds(
+	mut commands: Commands,
+	registry: Res<GizmoRegistry>,
+	action_state: Query<&ActionState<Action>>,
+	gizmos: Query<(Entity, &GizmoType, Has<Positive>, Has<Inactive>), Without<BeingPlaced>>,
+	mut edits: EventWriter<Edit>,
+) {
+	let action_state = action_state.single().unwrap();
+	if !action_state.pressed(&Action::ToggleModifier) {
+		return;
+	}
+	for gizmo in registry.iter() {
+		let gizmo_type = gizmo.gizmo_type();
+		for (_, positive) in gizmo.variants().each() {
+			if !action_state.just_pressed(&Action::Gizmo(GizmoAction {
+				gizmo_type,
+				positive,
+			})) {
+				continue;
+			}
+			let matching: Vec<(Entity, bool)> = gizmos
+				.iter()
+				.filter(|(_, other_type, other_positive, _)| {
+					**other_type == gizmo_type && *other_positive == positive
+				})
+				.map(|(entity, _, _, inactive)| (entity, inactive))
+				.collect();
+			let all_inactive = matching.iter().all(|(_, inactive)| *inactive);
+			// Only the gizmos that need to change are toggled, so undoing restores the mix there was before.
+			let toggled: Vec<Entity> = matching
+				.into_iter()
+				.filter(|(_, inactive)| *inactive == all_inactive)
+				.map(|(entity, _)| entity)
+				.collect();
+			if toggled.is_empty() {
+				continue;
+			}
+			let toggling = toggled.clone();
+			commands.queue(move |world: &mut World| toggle_gizmos(world, &toggling));
+			edits.write(Edit::Toggled(toggled));
+		}
+	}
+}
+
+fn dim_inactive_gizmos(
+	mut switched_off: Query<&mut Sprite, (With<GizmoType>, Added<Inactive>)>,
+	mut switched_on: RemovedComponents<Inactive>,
+	mut active: Query<&mut Sprite, (With<GizmoType>, Without<Inactive>)>,
+) {
+	for mut sprite in &mut switched_off {
+		sprite.color.set_alpha(INACTIVE_ALPHA);
+	}
+	for entity in switched_on.read() {
+		if let Ok(mut sprite) = active.get_mut(entity) {
+			sprite.color.set_alpha(1.0);
+		}
+	}
+}
@@ -0,0 +1,139 @@
+use bevy::{ecs::system::EntityCommands, prelude::*};
+
+use crate::{
+	TIMESTEP, WindowDimensions, WrappingForce,
+	common::{calculate_force, offset_2d},
+	draw_properties,
+	gizmos::{
+		EffectPainter, GizmoDefinition, GizmoParameters, GizmoType, GizmoVariant, GizmoVariants,
+		Inactive, overlay::EFFECT_AREA_COLOR,
+	},
+	movement::{Movement, MovementTrait, merge_speed},
+	particle::Particle,
+};
+
+pub struct VortexGizmo;
+
+impl GizmoDefinition for VortexGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Vortex")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Neutral(GizmoVariant {
+			key: KeyCode::Digit4,
+			draw_properties: draw_properties::VORTEX,
+		})
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert(Vortex::default());
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Vortex>(&[
+			"force",
+			"fall_off",
+			"proximity_cap",
+			"radius",
+		]))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(vortex) = entity.get::<Vortex>() {
+			painter.circle(vortex.radius, EFFECT_AREA_COLOR);
+		}
+	}
+	fn build(&self, app: &mut App) {
+		app.add_systems(FixedUpdate, activate_vortices.before(merge_speed));
+	}
+}
+
+/// A clockwise [`Vortex`]. Relies on [`VortexGizmo`] for its systems.
+pub struct ClockwiseVortexGizmo;
+
+impl GizmoDefinition for ClockwiseVortexGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Clockwise vortex")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Neutral(GizmoVariant {
+			key: KeyCode::Digit5,
+			draw_properties: draw_properties::CLOCKWISE_VORTEX,
+		})
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert(Vortex::clockwise());
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Vortex>(&[
+			"force",
+			"fall_off",
+			"proximity_cap",
+			"radius",
+		]))
+	}
+	fn draw_effect_area(&self, entity: &EntityRef, painter: &mut EffectPainter) {
+		if let Some(vortex) = entity.get::<Vortex>() {
+			painter.circle(vortex.radius, EFFECT_AREA_COLOR);
+		}
+	}
+}
+
+/// Swirls particles within its radius around itself, perpendicular to the direction an [`Attractor`](super::attractor::Attractor) would pull them.
+#[derive(Component, Reflect)]
+pub struct Vortex {
+	force: f32,
+	fall_off: f32,
+	proximity_cap: f32,
+	radius: f32,
+	clockwise: bool,
+}
+
+impl Vortex {
+	pub fn clockwise() -> Self {
+		Self {
+			clockwise: true,
+			..default()
+		}
+	}
+}
+
+impl Default for Vortex {
+	fn default() -> Self {
+		Self {
+			force: 10000.0,
+			fall_off: 1.05,
+			proximity_cap: 10.0,
+			radius: 300.0,
+			clockwise: false,
+		}
+	}
+}
+
+pub fn activate_vortices(
+	window_dimensions: Res<WindowDimensions>,
+	wrapping: Res<WrappingForce>,
+	vortices: Query<(&Vortex, &Transform), Without<Inactive>>,
+	mut particles: Query<(&mut Movement, &Transform), With<Particle>>,
+) {
+	for (vortex, vortex_transform) in &vortices {
+		let vortex_position = vortex_transform.translation.truncate();
+		for (mut movement, particle_transform) in &mut particles {
+			let offset = offset_2d(
+				vortex_position,
+				particle_transform.translation.truncate(),
+				wrapping.0.then_some(window_dimensions.0),
+			);
+			if offset.length_squared() > vortex.radius.powi(2) {
+				continue;
+			}
+			let pull = calculate_force(vortex.force, vortex.proximity_cap, vortex.fall_off, offset)
+				* TIMESTEP;
+			// The offset points from the particle to the vortex, so turning the pull clockwise makes particles go counter-clockwise.
+			let swirl = if vortex.clockwise {
+				pull.perp()
+			} else {
+				-pull.perp()
+			};
+
+			movement.add(swirl);
+		}
+	}
+}
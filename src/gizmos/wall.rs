@@ -0,0 +1,179 @@
+use bevy::{ecs::system::EntityCommands, prelude::*, sprite::Anchor};
+
+use crate::{
+	WindowDimensions,
+	common::wrapping_offset_2d,
+	draw_properties,
+	gizmos::{
+		BeingPlaced, GizmoDefinition, GizmoParameters, GizmoType, GizmoVariant, GizmoVariants,
+		Inactive, PlacementStyle,
+	},
+	movement::{Inertia, Movement, apply_movement, clamp_speed},
+	particle::ForceBarriers,
+};
+
+pub struct WallGizmo;
+
+impl GizmoDefinition for WallGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Wall")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Neutral(GizmoVariant {
+			key: KeyCode::Digit6,
+			draw_properties: draw_properties::WALL,
+		})
+	}
+	fn placement_style(&self) -> PlacementStyle {
+		PlacementStyle::WithRotation
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert(Wall::default());
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Wall>(&["length"]))
+	}
+	fn drag(&self, entity_commands: &mut EntityCommands, offset: Vec2) {
+		drag_wall(entity_commands, offset);
+	}
+	fn build(&self, app: &mut App) {
+		app.add_systems(Update, apply_wall_size)
+			.add_systems(FixedPreUpdate, collect_force_barriers)
+			.add_systems(
+				FixedUpdate,
+				collide_with_walls.after(clamp_speed).before(apply_movement),
+			);
+	}
+}
+
+/// A [`Wall`] that lets the force between particles through. Relies on [`WallGizmo`] for its systems.
+pub struct FenceGizmo;
+
+impl GizmoDefinition for FenceGizmo {
+	fn gizmo_type(&self) -> GizmoType {
+		GizmoType("Fence")
+	}
+	fn variants(&self) -> GizmoVariants {
+		GizmoVariants::Neutral(GizmoVariant {
+			key: KeyCode::Digit7,
+			draw_properties: draw_properties::FENCE,
+		})
+	}
+	fn placement_style(&self) -> PlacementStyle {
+		PlacementStyle::WithRotation
+	}
+	fn insert_components(&self, entity_commands: &mut EntityCommands) {
+		entity_commands.insert(Wall::fence());
+	}
+	fn parameters(&self) -> Option<GizmoParameters> {
+		Some(GizmoParameters::new::<Wall>(&["length"]))
+	}
+	fn drag(&self, entity_commands: &mut EntityCommands, offset: Vec2) {
+		drag_wall(entity_commands, offset);
+	}
+}
+
+/// How thick walls are drawn.
+const WALL_THICKNESS: f32 = 4.0;
+/// How close to a wall anything moving can get.
+const WALL_MARGIN: f32 = 3.0;
+/// Dragging while placing a wall can't make it shorter than this, so placing one without dragging still leaves a wall.
+const MIN_WALL_LENGTH: f32 = 40.0;
+
+/// A line segment that moving things cannot cross. It runs from the gizmo's position along its rotation.
+#[derive(Component, Reflect)]
+pub struct Wall {
+	length: f32,
+	/// Whether particles on opposite sides exert no force on each other.
+	blocks_forces: bool,
+}
+
+impl Wall {
+	pub fn fence() -> Self {
+		Self {
+			blocks_forces: false,
+			..default()
+		}
+	}
+	fn end_points(&self, transform: &Transform) -> (Vec2, Vec2) {
+		let start = transform.translation.truncate();
+		let direction = (transform.rotation * Vec3::X).truncate();
+		(start, start + direction * self.length)
+	}
+}
+
+impl Default for Wall {
+	fn default() -> Self {
+		Self {
+			length: MIN_WALL_LENGTH,
+			blocks_forces: true,
+		}
+	}
+}
+
+fn drag_wall(entity_commands: &mut EntityCommands, offset: Vec2) {
+	let length = offset.length().max(MIN_WALL_LENGTH);
+	entity_commands
+		.entry::<Wall>()
+		.and_modify(move |mut wall| wall.length = length);
+}
+
+fn apply_wall_size(mut walls: Query<(&Wall, &mut Sprite), Changed<Wall>>) {
+	for (wall, mut sprite) in &mut walls {
+		sprite.custom_size = Some(Vec2::new(wall.length, WALL_THICKNESS));
+		sprite.anchor = Anchor::CenterLeft;
+	}
+}
+
+fn collect_force_barriers(
+	mut barriers: ResMut<ForceBarriers>,
+	walls: Query<(&Wall, &Transform), (Without<BeingPlaced>, Without<Inactive>)>,
+) {
+	barriers.0.clear();
+	barriers.0.extend(
+		walls
+			.iter()
+			.filter(|(wall, _)| wall.blocks_forces)
+			.map(|(wall, transform)| wall.end_points(transform)),
+	);
+}
+
+/// Stops anything about to move through or too close to a wall, keeping only its movement along the wall, so it slides along it.
+fn collide_with_walls(
+	window_dimensions: Res<WindowDimensions>,
+	inertia: Res<Inertia>,
+	walls: Query<(&Wall, &Transform), (Without<BeingPlaced>, Without<Inactive>)>,
+	mut movers: Query<(&mut Movement, &Transform)>,
+) {
+	for (wall, wall_transform) in &walls {
+		let direction = (wall_transform.rotation * Vec3::X).truncate();
+		let normal = direction.perp();
+		let middle = wall_transform.translation.truncate() + direction * wall.length / 2.0;
+
+		for (mut movement, transform) in &mut movers {
+			let offset = wrapping_offset_2d(
+				transform.translation.truncate(),
+				middle,
+				window_dimensions.0,
+			);
+			let displacement = inertia.displacement(movement.get());
+			let along = offset.dot(direction) + displacement.dot(direction);
+			if along.abs() > wall.length / 2.0 + WALL_MARGIN {
+				continue;
+			}
+			let distance = offset.dot(normal);
+			let side = if distance != 0.0 {
+				distance.signum()
+			} else {
+				-displacement.dot(normal).signum()
+			};
+			let new_distance = distance + displacement.dot(normal);
+			if new_distance * side >= WALL_MARGIN {
+				continue;
+			}
+			let corrected =
+				displacement.dot(direction) * direction + (side * WALL_MARGIN - distance) * normal;
+			movement.set(inertia.movement(corrected));
+		}
+	}
+}
@@ -1,22 +1,39 @@
 use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
 use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
 
+use crate::WindowDimensions;
 use crate::gizmos::ParticleLimit;
+use crate::input::Action;
 use crate::particle::Particle;
+use crate::stats::{RADIAL_DISTRIBUTION_RADIUS, Statistic, StatsHistory};
 
 pub struct GuiPlugin;
 
 impl Plugin for GuiPlugin {
 	fn build(&self, app: &mut App) {
 		app.add_plugins(FrameTimeDiagnosticsPlugin::default())
-			.add_systems(Startup, set_up_panels)
+			.init_resource::<StatsPanel>()
+			.add_systems(Startup, (set_up_panels, set_up_stats_panel))
 			.add_systems(
 				Update,
-				(update_fps, update_particle_count, update_particle_limit),
+				(
+					update_fps,
+					update_particle_count,
+					update_particle_limit,
+					(toggle_stats_panel, update_stats_labels, draw_stats_graphs).chain(),
+				),
 			);
 	}
 }
 
+/// Distance of the statistics panel from the top left corner of the window.
+const STATS_PANEL_MARGIN: f32 = 15.0;
+const GRAPH_SIZE: Vec2 = Vec2::new(240.0, 50.0);
+const GRAPH_SPACING: f32 = 10.0;
+const GRAPH_FRAME_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.3);
+const GRAPH_LINE_COLOR: Color = Color::srgb(1.0, 0.8, 0.3);
+
 #[derive(Component)]
 struct FpsDisplay;
 
@@ -26,6 +43,19 @@ struct ParticleCountDisplay;
 #[derive(Component)]
 struct ParticleLimitDisplay;
 
+/// Whether the panel of statistics graphs is shown.
+#[derive(Resource, Default)]
+struct StatsPanel(bool);
+
+#[derive(Component)]
+struct StatsPanelNode;
+
+#[derive(Component)]
+struct StatisticLabel(Statistic);
+
+#[derive(Component)]
+struct RadialDistributionLabel;
+
 fn set_up_panels(mut commands: Commands, asset_server: Res<AssetServer>) {
 	let font = asset_server.load("fonts/FiraSans-Bold.ttf");
 	commands.spawn((
@@ -100,3 +130,162 @@ fn update_particle_limit(
 
 	text.0 = format!("/ {}", limit.current());
 }
+
+fn set_up_stats_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+	let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+	commands
+		.spawn((
+			Node {
+				display: Display::None,
+				position_type: PositionType::Absolute,
+				top: Val::Px(STATS_PANEL_MARGIN),
+				left: Val::Px(STATS_PANEL_MARGIN),
+				flex_direction: FlexDirection::Column,
+				row_gap: Val::Px(GRAPH_SPACING),
+				..default()
+			},
+			StatsPanelNode,
+		))
+		.with_children(|panel| {
+			for statistic in Statistic::ALL {
+				panel.spawn((
+					Text(String::from(statistic.name())),
+					TextFont::from_font(font.clone()).with_font_size(14.0),
+					TextColor(Color::WHITE),
+					Node {
+						width: Val::Px(GRAPH_SIZE.x),
+						height: Val::Px(GRAPH_SIZE.y),
+						padding: UiRect::all(Val::Px(3.0)),
+						..default()
+					},
+					StatisticLabel(statistic),
+				));
+			}
+			panel.spawn((
+				Text(String::from("g(r)")),
+				TextFont::from_font(font.clone()).with_font_size(14.0),
+				TextColor(Color::WHITE),
+				Node {
+					width: Val::Px(GRAPH_SIZE.x),
+					height: Val::Px(GRAPH_SIZE.y),
+					padding: UiRect::all(Val::Px(3.0)),
+					..default()
+				},
+				RadialDistributionLabel,
+			));
+		});
+}
+
+fn toggle_stats_panel(
+	mut panel: ResMut<StatsPanel>,
+	action_state: Query<&ActionState<Action>>,
+	mut node: Query<&mut Node, With<StatsPanelNode>>,
+) {
+	if action_state
+		.single()
+		.unwrap()
+		.just_pressed(&Action::ToggleStatsPanel)
+	{
+		panel.0 = !panel.0;
+		node.single_mut().unwrap().display = if panel.0 {
+			Display::Flex
+		} else {
+			Display::None
+		};
+	}
+}
+
+fn update_stats_labels(
+	panel: Res<StatsPanel>,
+	history: Res<StatsHistory>,
+	mut labels: Query<(&mut Text, &StatisticLabel)>,
+	mut radial_distribution_label: Query<&mut Text, With<RadialDistributionLabel>>,
+) {
+	if !panel.0 {
+		return;
+	}
+	for (mut text, label) in &mut labels {
+		let statistic = label.0;
+		text.0 = match history.latest(statistic) {
+			Some(value)
+				if matches!(
+					statistic,
+					Statistic::DensityVariation | Statistic::SpacingRatio
+				) =>
+			{
+				format!("{}: {value:.2}", statistic.name())
+			}
+			Some(value) => format!("{}: {value:.1}", statistic.name()),
+			None => String::from(statistic.name()),
+		};
+	}
+	let mut text = radial_distribution_label.single_mut().unwrap();
+	text.0 = match history.nearest_neighbours() {
+		Some(summary) => format!(
+			"g(r), r < {RADIAL_DISTRIBUTION_RADIUS}. NN: {:.1} ± {:.1}",
+			summary.mean, summary.standard_deviation
+		),
+		None => format!("g(r), r < {RADIAL_DISTRIBUTION_RADIUS}"),
+	};
+}
+
+/// Draws a line graph behind every statistic label, and the radial distribution function below them. The vertical scale of each graph runs from 0 to its highest value.
+fn draw_stats_graphs(
+	mut gizmos: Gizmos,
+	panel: Res<StatsPanel>,
+	window_dimensions: Res<WindowDimensions>,
+	history: Res<StatsHistory>,
+) {
+	if !panel.0 {
+		return;
+	}
+	// UI positions are measured from the top, the world from the bottom.
+	let top = window_dimensions.get().y - STATS_PANEL_MARGIN;
+	let bottom_left = |index: usize| {
+		Vec2::new(
+			STATS_PANEL_MARGIN,
+			top - (index + 1) as f32 * GRAPH_SIZE.y - index as f32 * GRAPH_SPACING,
+		)
+	};
+	for (index, statistic) in Statistic::ALL.into_iter().enumerate() {
+		draw_graph(
+			&mut gizmos,
+			bottom_left(index),
+			history.samples(statistic).iter().copied(),
+			StatsHistory::CAPACITY,
+		);
+	}
+	let radial_distribution = history.radial_distribution();
+	draw_graph(
+		&mut gizmos,
+		bottom_left(Statistic::ALL.len()),
+		radial_distribution.iter().copied(),
+		radial_distribution.len(),
+	);
+}
+
+/// Draws a frame with a line through the values, with `capacity` values filling the full width.
+fn draw_graph(
+	gizmos: &mut Gizmos,
+	bottom_left: Vec2,
+	values: impl Iterator<Item = f32> + Clone,
+	capacity: usize,
+) {
+	gizmos.rect_2d(
+		bottom_left + GRAPH_SIZE / 2.0,
+		GRAPH_SIZE,
+		GRAPH_FRAME_COLOR,
+	);
+
+	let max = values.clone().fold(0.0, f32::max);
+	if max <= 0.0 || capacity < 2 {
+		return;
+	}
+	let step = GRAPH_SIZE.x / (capacity - 1) as f32;
+	gizmos.linestrip_2d(
+		values.enumerate().map(|(index, value)| {
+			bottom_left + Vec2::new(index as f32 * step, value / max * GRAPH_SIZE.y)
+		}),
+		GRAPH_LINE_COLOR,
+	);
+}
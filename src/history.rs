@@ -0,0 +1,173 @@
+//! Undo and redo. Systems making an edit send an [`Edit`] describing how to revert it. Deleted entities are disabled rather than despawned, so they can come back, and are only despawned once no edit can bring them back.
+
+use std::collections::VecDeque;
+
+use bevy::{ecs::entity_disabling::Disabled, prelude::*};
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+	gizmos::{GizmoParameters, GizmoRegistry, GizmoType, Motion, ParticleLimit, toggle_gizmos},
+	input::Action,
+};
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_event::<Edit>()
+			.init_resource::<History>()
+			.add_systems(PostUpdate, (record_edits, undo_or_redo).chain());
+	}
+}
+
+/// How many edits can be undone.
+const CAPACITY: usize = 200;
+
+/// An edit that was made, holding what is needed to revert it.
+#[derive(Event)]
+pub enum Edit {
+	/// These entities were spawned together, such as a particle, or a gizmo.
+	Spawned(Vec<Entity>),
+	/// These entities were deleted together, and are now disabled.
+	Deleted(Vec<Entity>),
+	/// A gizmo was moved, turned, set in motion or had its parameters changed. Holds the state before.
+	Changed { entity: Entity, before: GizmoState },
+	/// These gizmos were switched on or off.
+	Toggled(Vec<Entity>),
+	/// The particle limit was changed. Holds the limit before.
+	ParticleLimit(u32),
+}
+
+impl Edit {
+	/// Reverts this edit, returning the edit that reverts that again.
+	fn revert(self, world: &mut World) -> Self {
+		match self {
+			Self::Spawned(entities) => {
+				for &entity in &entities {
+					if let Ok(mut entity) = world.get_entity_mut(entity) {
+						entity.insert(Disabled);
+					}
+				}
+				Self::Deleted(entities)
+			}
+			Self::Deleted(entities) => {
+				for &entity in &entities {
+					if let Ok(mut entity) = world.get_entity_mut(entity) {
+						entity.remove::<Disabled>();
+					}
+				}
+				Self::Spawned(entities)
+			}
+			Self::Changed { entity, before } => {
+				let Ok(entity_ref) = world.get_entity(entity) else {
+					return Self::Changed { entity, before };
+				};
+				let parameters = GizmoState::parameters(world, &entity_ref);
+				let current = GizmoState::capture(&entity_ref, parameters);
+				before.restore(&mut world.entity_mut(entity), parameters);
+				Self::Changed {
+					entity,
+					before: current,
+				}
+			}
+			Self::Toggled(entities) => {
+				toggle_gizmos(world, &entities);
+				Self::Toggled(entities)
+			}
+			Self::ParticleLimit(before) => {
+				let mut limit = world.resource_mut::<ParticleLimit>();
+				let current = limit.current();
+				limit.set(before);
+				Self::ParticleLimit(current)
+			}
+		}
+	}
+	/// Called when this edit is dropped from history. Entities that are disabled and can no longer come back are despawned.
+	fn discard(self, commands: &mut Commands) {
+		if let Self::Deleted(entities) = self {
+			for entity in entities {
+				commands.entity(entity).try_despawn();
+			}
+		}
+	}
+}
+
+/// Everything about a gizmo that can be changed after spawning it.
+pub struct GizmoState {
+	transform: Transform,
+	motion: Option<Motion>,
+	parameters: Option<Box<dyn PartialReflect>>,
+}
+
+impl GizmoState {
+	pub fn capture(entity: &EntityRef, parameters: Option<GizmoParameters>) -> Self {
+		Self {
+			transform: entity.get::<Transform>().copied().unwrap_or_default(),
+			motion: entity.get::<Motion>().cloned(),
+			parameters: parameters.and_then(|parameters| parameters.snapshot(entity)),
+		}
+	}
+	pub fn transform(&self) -> Transform {
+		self.transform
+	}
+	/// The parameters of the gizmo's kind, if it has any.
+	pub fn parameters(world: &World, entity: &EntityRef) -> Option<GizmoParameters> {
+		entity
+			.get::<GizmoType>()
+			.and_then(|gizmo_type| world.resource::<GizmoRegistry>().get(*gizmo_type))
+			.and_then(|gizmo| gizmo.parameters())
+	}
+	fn restore(&self, entity: &mut EntityWorldMut, parameters: Option<GizmoParameters>) {
+		if let Some(mut transform) = entity.get_mut::<Transform>() {
+			*transform = self.transform;
+		}
+		match &self.motion {
+			Some(motion) => entity.insert(motion.clone()),
+			None => entity.remove::<Motion>(),
+		};
+		if let Some((parameters, snapshot)) = parameters.zip(self.parameters.as_deref()) {
+			parameters.restore(entity, snapshot);
+		}
+	}
+}
+
+#[derive(Resource, Default)]
+struct History {
+	undo: VecDeque<Edit>,
+	redo: Vec<Edit>,
+}
+
+fn record_edits(
+	mut commands: Commands,
+	mut edits: ResMut<Events<Edit>>,
+	mut history: ResMut<History>,
+) {
+	for edit in edits.drain() {
+		history.undo.push_back(edit);
+		if history.undo.len() > CAPACITY {
+			history.undo.pop_front().unwrap().discard(&mut commands);
+		}
+		for edit in history.redo.drain(..) {
+			edit.discard(&mut commands);
+		}
+	}
+}
+
+fn undo_or_redo(world: &mut World) {
+	let mut action_state = world.query::<&ActionState<Action>>();
+	let Ok(action_state) = action_state.single(world) else {
+		return;
+	};
+	let undo = action_state.just_pressed(&Action::Undo);
+	let redo = action_state.just_pressed(&Action::Redo);
+
+	world.resource_scope(|world, mut history: Mut<History>| {
+		if undo && let Some(edit) = history.undo.pop_back() {
+			let reverted = edit.revert(world);
+			history.redo.push(reverted);
+		} else if redo && let Some(edit) = history.redo.pop() {
+			let reverted = edit.revert(world);
+			history.undo.push_back(reverted);
+		}
+	});
+}
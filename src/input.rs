@@ -1,6 +1,8 @@
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::*;
 
+use crate::gizmos::{GizmoAction, GizmoRegistry};
+
 pub struct InputPlugin;
 
 impl Plugin for InputPlugin {
@@ -13,52 +15,92 @@ impl Plugin for InputPlugin {
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Reflect, Actionlike)]
 pub enum Action {
 	SpawnParticle,
-	PositiveEmitter,
-	NegativeEmitter,
-	Deleter,
-	SlowDeleter,
-	Attractor,
-	Repulsor,
-	PositiveEater,
-	NegativeEater,
-	Pusher,
+	/// Spawns or deletes the gizmo variant. Bound according to the [`GizmoRegistry`].
+	Gizmo(GizmoAction),
 	DespawnModifier,
 	DespawnAllModifier,
+	/// Switches gizmos on or off instead of spawning them, or, when clicking, selecting them.
+	ToggleModifier,
 	RaiseParticleLimit,
 	LowerParticleLimit,
 	ToggleInertia,
 	ToggleWrap,
+	ToggleTrails,
+	CycleColorMode,
+	ToggleStatsPanel,
+	ToggleMetricsRecording,
+	ToggleTrajectoryRecording,
+	ToggleEffectOverlay,
+	CycleDecayMode,
+	RaiseDecayRate,
+	LowerDecayRate,
+	/// Cycles the motion of the gizmo under the cursor when tapped, or draws a path for it while held.
+	SetMotion,
+	/// Wires the gizmo under the cursor to the selected sensor, or unwires it.
+	WireSensor,
+	/// Turns a gizmo placed with rotation while held over it.
+	RotateGizmo,
+	/// Picks which field of the selected gizmo scrolling over it adjusts.
+	CycleAdjustedField,
+	/// Snaps dragged gizmos to a grid, and their rotation to set angles.
+	SnapModifier,
+	Undo,
+	Redo,
+	/// Scales the primary parameter of the gizmo under the cursor.
+	#[actionlike(Axis)]
+	ScaleGizmo,
 }
 
-fn set_binds(mut commands: Commands) {
+fn set_binds(mut commands: Commands, registry: Res<GizmoRegistry>) {
 	use Action::*;
 	use KeyCode::*;
 
-	let actions = vec![
-		(Equal, PositiveEmitter),
-		(Minus, NegativeEmitter),
-		(Digit1, Deleter),
-		(Backquote, SlowDeleter),
-		(Digit2, Attractor),
-		(Digit3, Repulsor),
-		(BracketLeft, NegativeEater),
-		(BracketRight, PositiveEater),
-		(Period, Pusher),
-	];
-
 	let mut input_map = InputMap::default();
 	input_map.insert(SpawnParticle, MouseButton::Left);
 	input_map.insert(DespawnAllModifier, ControlLeft);
 	input_map.insert(DespawnAllModifier, ControlRight);
 	input_map.insert(DespawnModifier, ShiftLeft);
 	input_map.insert(DespawnModifier, ShiftRight);
+	input_map.insert(ToggleModifier, AltLeft);
+	input_map.insert(ToggleModifier, AltRight);
 	input_map.insert(RaiseParticleLimit, ArrowUp);
 	input_map.insert(LowerParticleLimit, ArrowDown);
 	input_map.insert(ToggleInertia, KeyI);
 	input_map.insert(ToggleWrap, KeyW);
+	input_map.insert(ToggleTrails, KeyT);
+	input_map.insert(CycleColorMode, KeyC);
+	input_map.insert(ToggleStatsPanel, KeyG);
+	input_map.insert(ToggleMetricsRecording, KeyR);
+	input_map.insert(ToggleTrajectoryRecording, KeyE);
+	input_map.insert(ToggleEffectOverlay, KeyA);
+	input_map.insert(CycleDecayMode, KeyD);
+	input_map.insert(RaiseDecayRate, PageUp);
+	input_map.insert(LowerDecayRate, PageDown);
+	input_map.insert(SetMotion, KeyX);
+	input_map.insert(WireSensor, Tab);
+	input_map.insert(RotateGizmo, MouseButton::Right);
+	input_map.insert(CycleAdjustedField, Space);
+	input_map.insert(SnapModifier, ControlLeft);
+	input_map.insert(SnapModifier, ControlRight);
+	input_map.insert_axis(ScaleGizmo, MouseScrollAxis::Y);
+	// Neither key is bound to a gizmo, so these chords can't be mistaken for deleting all gizmos of a kind. `KeyCode` has its own `Undo` and `Redo`, so these need the full path.
+	input_map.insert(
+		Action::Undo,
+		ButtonlikeChord::modified(ModifierKey::Control, KeyZ),
+	);
+	input_map.insert(
+		Action::Redo,
+		ButtonlikeChord::modified(ModifierKey::Control, KeyY),
+	);
 
-	for (key, action) in actions {
-		input_map.insert(action, key);
+	for gizmo in registry.iter() {
+		for (variant, positive) in gizmo.variants().each() {
+			let action = Gizmo(GizmoAction {
+				gizmo_type: gizmo.gizmo_type(),
+				positive,
+			});
+			input_map.insert(action, variant.key);
+		}
 	}
 
 	commands.spawn(input_map);
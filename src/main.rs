@@ -2,24 +2,39 @@ use bevy::{
 	prelude::*,
 	window::{PrimaryWindow, WindowResolution},
 };
+use cli::CommandLine;
+use decay::DecayPlugin;
 use gizmos::GizmoPlugin;
 use gui::GuiPlugin;
+use history::HistoryPlugin;
 use input::{Action, InputPlugin};
 use leafwing_input_manager::prelude::ActionState;
 use movement::MovementPlugin;
 use particle::ParticlePlugin;
+use recorder::RecorderPlugin;
+use stats::StatsPlugin;
+use trajectory::TrajectoryPlugin;
+use visuals::VisualsPlugin;
 
 use crate::assets::{TextureMap, load_assets};
 
+mod analysis;
 mod assets;
+mod cli;
 mod common;
+mod decay;
 mod draw_properties;
 mod gizmos;
 mod gui;
+mod history;
 mod input;
 mod macros;
 mod movement;
 mod particle;
+mod recorder;
+mod stats;
+mod trajectory;
+mod visuals;
 
 pub const CLICK_RADIUS: f32 = 15.0;
 pub const CLICK_RADIUS_SQUARED: f32 = CLICK_RADIUS * CLICK_RADIUS;
@@ -27,8 +42,16 @@ pub const CLICK_RADIUS_SQUARED: f32 = CLICK_RADIUS * CLICK_RADIUS;
 pub const TIMESTEP: f32 = 1.0 / 60.0;
 
 fn main() {
+	let command_line = match CommandLine::parse(std::env::args().skip(1)) {
+		Ok(command_line) => command_line,
+		Err(message) => {
+			eprintln!("{message}");
+			std::process::exit(2);
+		}
+	};
 	let window_size = Vec2::new(1600.0, 900.0);
 	App::new()
+		.insert_resource(command_line)
 		.insert_resource(ClearColor(Color::srgb(0.1, 0.1, 0.1)))
 		.insert_resource(WindowDimensions(window_size))
 		.insert_resource(Time::<Fixed>::from_seconds(TIMESTEP as f64))
@@ -45,8 +68,14 @@ fn main() {
 			InputPlugin,
 			MovementPlugin,
 			ParticlePlugin,
+			DecayPlugin,
 			GizmoPlugin,
 			GuiPlugin,
+			HistoryPlugin,
+			StatsPlugin,
+			RecorderPlugin,
+			TrajectoryPlugin,
+			VisualsPlugin,
 		))
 		.add_systems(Startup, (spawn_camera, load_assets))
 		.init_resource::<TextureMap>()
@@ -19,7 +19,7 @@ impl Plugin for MovementPlugin {
 }
 
 /// Maximum speed of any movement in units/second.
-const MAX_SPEED: f32 = 200.0;
+pub const MAX_SPEED: f32 = 200.0;
 
 pub trait MovementTrait {
 	fn add(&mut self, other: Vec2);
@@ -28,6 +28,15 @@ pub trait MovementTrait {
 #[derive(Default, Component)]
 pub struct Movement(Vec2);
 
+impl Movement {
+	pub fn get(&self) -> Vec2 {
+		self.0
+	}
+	pub fn set(&mut self, movement: Vec2) {
+		self.0 = movement;
+	}
+}
+
 impl MovementTrait for Movement {
 	fn add(&mut self, movement: Vec2) {
 		self.0 += movement;
@@ -37,6 +46,16 @@ impl MovementTrait for Movement {
 #[derive(Default, Component)]
 pub struct MovementBatch2(Vec2);
 
+/// The movement that was last applied to an entity, in units/second. Unlike [`Movement`], this is not cleared after being applied.
+#[derive(Default, Component)]
+pub struct Velocity(Vec2);
+
+impl Velocity {
+	pub fn get(&self) -> Vec2 {
+		self.0
+	}
+}
+
 impl MovementTrait for MovementBatch2 {
 	fn add(&mut self, movement: Vec2) {
 		self.0 += movement;
@@ -46,14 +65,13 @@ impl MovementTrait for MovementBatch2 {
 pub fn apply_movement(
 	window_dimensions: Res<WindowDimensions>,
 	inertia: Res<Inertia>,
-	mut movers: Query<(&mut Transform, &mut Movement)>,
+	mut movers: Query<(&mut Transform, &mut Movement, Option<&mut Velocity>)>,
 ) {
-	for (mut transform, mut movement) in &mut movers {
-		let movement_to_apply = if inertia.0 {
-			movement.0 * TIMESTEP * 0.5
-		} else {
-			movement.0
-		};
+	for (mut transform, mut movement, velocity) in &mut movers {
+		let movement_to_apply = inertia.displacement(movement.0);
+		if let Some(mut velocity) = velocity {
+			velocity.0 = movement_to_apply / TIMESTEP;
+		}
 		transform.translation += movement_to_apply.extend(0.0);
 		transform.translation.x = transform.translation.x.rem_euclid(window_dimensions.0.x);
 		transform.translation.y = transform.translation.y.rem_euclid(window_dimensions.0.y);
@@ -66,6 +84,28 @@ pub fn apply_movement(
 #[derive(Resource, Default)]
 pub struct Inertia(bool);
 
+impl Inertia {
+	pub fn is_enabled(&self) -> bool {
+		self.0
+	}
+	/// How far an entity with this movement moves when it is next applied. The inverse of [`Inertia::movement`].
+	pub fn displacement(&self, movement: Vec2) -> Vec2 {
+		if self.0 {
+			movement * TIMESTEP * 0.5
+		} else {
+			movement
+		}
+	}
+	/// The movement that would result in this displacement. The inverse of [`Inertia::displacement`].
+	pub fn movement(&self, displacement: Vec2) -> Vec2 {
+		if self.0 {
+			displacement / (TIMESTEP * 0.5)
+		} else {
+			displacement
+		}
+	}
+}
+
 pub fn toggle_inertia(mut inertia: ResMut<Inertia>, action_state: Query<&ActionState<Action>>) {
 	if action_state
 		.single()
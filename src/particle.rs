@@ -1,5 +1,9 @@
 use bevy::{
-	ecs::query::{QueryFilter, WorldQuery},
+	ecs::{
+		entity_disabling::Disabled,
+		query::{QueryFilter, WorldQuery},
+		system::SystemParam,
+	},
 	prelude::*,
 	window::PrimaryWindow,
 };
@@ -8,10 +12,17 @@ use leafwing_input_manager::prelude::ActionState;
 use crate::{
 	TIMESTEP, WindowDimensions, WrappingForce,
 	assets::TextureMap,
-	common::{Positive, calculate_force, circular_points, offset_2d, wrapping_offset_2d},
+	common::{
+		Positive, calculate_force, circular_points, offset_2d, segments_intersect,
+		wrapping_offset_2d,
+	},
+	decay::{Charge, Decay, Inert},
 	draw_properties::{self, DrawProperties},
+	gizmos::HoveredGizmo,
+	history::Edit,
 	input::Action,
-	movement::{Movement, MovementBatch2, MovementTrait, merge_speed},
+	movement::{Movement, MovementBatch2, MovementTrait, Velocity, merge_speed},
+	stats::EventTotals,
 	unwrap_or_return,
 };
 
@@ -20,6 +31,8 @@ pub struct ParticlePlugin;
 impl Plugin for ParticlePlugin {
 	fn build(&self, app: &mut App) {
 		app.init_resource::<NextBatch>()
+			.init_resource::<NextParticleId>()
+			.init_resource::<ForceBarriers>()
 			.add_systems(Startup, spawn_initial_particles)
 			.add_systems(
 				Update,
@@ -41,6 +54,7 @@ impl Plugin for ParticlePlugin {
 					)
 						.before(merge_speed),
 					(particles_cancelling, despawn_cancelled_particles).chain(),
+					age_particles,
 				),
 			);
 	}
@@ -63,15 +77,53 @@ pub struct Particle;
 #[derive(Default, Component)]
 pub struct Cancelled(pub bool);
 
+/// Identifies a particle for its whole life. Unlike [`Entity`], it is never reused after the particle is despawned.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParticleId(pub u64);
+
+#[derive(Resource, Default)]
+struct NextParticleId(u64);
+
+/// How many seconds old a particle is, scaled by [`Decay::rate`].
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct Age(pub f32);
+
+/// A particle that is despawned once its [`Age`] reaches this. It fades out shortly before.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Lifetime(pub f32);
+
+/// Line segments, as start and end points, that particles do not apply force across.
+#[derive(Resource, Default)]
+pub struct ForceBarriers(pub Vec<(Vec2, Vec2)>);
+
+impl ForceBarriers {
+	/// Whether any barrier lies between `from` and `from + offset`.
+	pub fn blocks(&self, wrap: Option<Vec2>, from: Vec2, offset: Vec2) -> bool {
+		self.0.iter().any(|&(start, end)| {
+			// Place the barrier in the same wrapping frame as the offset.
+			let start_offset = offset_2d(start, from, wrap);
+			segments_intersect(
+				Vec2::ZERO,
+				offset,
+				start_offset,
+				start_offset + (end - start),
+			)
+		})
+	}
+}
+
 fn spawn_particle(
 	mut commands: Commands,
 	window: Query<&Window, With<PrimaryWindow>>,
-	mut next_batch: ResMut<NextBatch>,
+	mut spawner: ParticleSpawner,
 	action_state: Query<&ActionState<Action>>,
+	hovered_gizmo: Res<HoveredGizmo>,
+	mut edits: EventWriter<Edit>,
 ) {
 	let action_state = action_state.single().unwrap();
 	if !action_state.just_pressed(&Action::SpawnParticle)
 		|| action_state.pressed(&Action::DespawnModifier)
+		|| hovered_gizmo.0.is_some()
 	{
 		return;
 	}
@@ -81,13 +133,16 @@ fn spawn_particle(
 			.map(|pos| Vec2::new(pos.x, window.height() - pos.y))
 	}));
 
-	spawn_particle_at_location(&mut commands, &mut next_batch, cursor_pos, true);
+	let particle = spawn_particle_at_location(&mut commands, &mut spawner, cursor_pos, true);
+	edits.write(Edit::Spawned(vec![particle]));
 }
 
+/// Particles are disabled rather than despawned, so this can be undone.
 fn despawn_all_particles(
 	mut commands: Commands,
 	action_state: Query<&ActionState<Action>>,
 	particles: Query<Entity, With<Particle>>,
+	mut edits: EventWriter<Edit>,
 ) {
 	let action_state = action_state.single().unwrap();
 	if !action_state.just_pressed(&Action::SpawnParticle)
@@ -97,15 +152,44 @@ fn despawn_all_particles(
 	}
 
 	for particle in &particles {
-		commands.entity(particle).despawn();
+		commands.entity(particle).insert(Disabled);
 	}
+	if !particles.is_empty() {
+		edits.write(Edit::Deleted(particles.iter().collect()));
+	}
+}
+
+/// The movement per tick a particle gets from another at `offset` from it: away from it if they have the same polarity, towards it otherwise.
+pub fn particle_force(offset: Vec2, positive_a: bool, positive_b: bool) -> Vec2 {
+	let force = calculate_force(BASE_FORCE, PROXIMITY_FORCE_CAP, DIMINISHING_POWER, offset);
+	let invert_force = if positive_a != positive_b { -1.0 } else { 1.0 };
+	force * TIMESTEP * invert_force
+}
+
+/// The distance at which the force between two particles moves them by `movement` per tick.
+pub fn particle_force_reach(movement: f32) -> f32 {
+	(BASE_FORCE * TIMESTEP / movement)
+		.powf(DIMINISHING_POWER.recip())
+		.max(PROXIMITY_FORCE_CAP)
+}
+
+/// How strongly two particles affect each other, as a fraction of full charge.
+fn charge_product(charge_a: Option<&Charge>, charge_b: Option<&Charge>) -> f32 {
+	charge_a.map_or(1.0, |charge| charge.0) * charge_b.map_or(1.0, |charge| charge.0)
 }
 
 fn particles_applying_forces<M, F, F2>(
 	window_dimensions: Res<WindowDimensions>,
 	wrapping: Res<WrappingForce>,
-	mut particles: Query<(&mut M, Option<&Positive>, &Transform), (With<Particle>, F)>,
-	other_particles: Query<(Option<&Positive>, &Transform), (With<Particle>, F2)>,
+	barriers: Res<ForceBarriers>,
+	mut particles: Query<
+		(&mut M, Option<&Positive>, Option<&Charge>, &Transform),
+		(With<Particle>, Without<Inert>, F),
+	>,
+	other_particles: Query<
+		(Option<&Positive>, Option<&Charge>, &Transform),
+		(With<Particle>, Without<Inert>, F2),
+	>,
 ) where
 	M: Component<Mutability = bevy::ecs::component::Mutable> + MovementTrait,
 	F: WorldQuery + QueryFilter,
@@ -114,50 +198,46 @@ fn particles_applying_forces<M, F, F2>(
 	let mut combinations = particles.iter_combinations_mut();
 	while let Some(
 		[
-			(mut movement_a, positive_a, transform_a),
-			(mut movement_b, positive_b, transform_b),
+			(mut movement_a, positive_a, charge_a, transform_a),
+			(mut movement_b, positive_b, charge_b, transform_b),
 		],
 	) = combinations.fetch_next()
 	{
-		let force = calculate_force(
-			BASE_FORCE,
-			PROXIMITY_FORCE_CAP,
-			DIMINISHING_POWER,
-			offset_2d(
-				transform_a.translation.truncate(),
-				transform_b.translation.truncate(),
-				wrapping.0.then_some(window_dimensions.0),
-			),
+		let offset = offset_2d(
+			transform_a.translation.truncate(),
+			transform_b.translation.truncate(),
+			wrapping.0.then_some(window_dimensions.0),
 		);
-		let invert_force = if positive_a.is_some() != positive_b.is_some() {
-			-1.0
-		} else {
-			1.0
-		};
-		let force = force * TIMESTEP * invert_force;
+		if barriers.blocks(
+			wrapping.0.then_some(window_dimensions.0),
+			transform_b.translation.truncate(),
+			offset,
+		) {
+			continue;
+		}
+		let force = particle_force(offset, positive_a.is_some(), positive_b.is_some())
+			* charge_product(charge_a, charge_b);
 
 		movement_a.add(force);
 		movement_b.add(-force);
 	}
 	drop(combinations);
-	for (mut movement, positive_a, transform_a) in &mut particles.iter_mut() {
-		for (positive_b, transform_b) in &other_particles {
-			let force = calculate_force(
-				BASE_FORCE,
-				PROXIMITY_FORCE_CAP,
-				DIMINISHING_POWER,
-				offset_2d(
-					transform_a.translation.truncate(),
-					transform_b.translation.truncate(),
-					wrapping.0.then_some(window_dimensions.0),
-				),
+	for (mut movement, positive_a, charge_a, transform_a) in &mut particles.iter_mut() {
+		for (positive_b, charge_b, transform_b) in &other_particles {
+			let offset = offset_2d(
+				transform_a.translation.truncate(),
+				transform_b.translation.truncate(),
+				wrapping.0.then_some(window_dimensions.0),
 			);
-			let invert_force = if positive_a.is_some() != positive_b.is_some() {
-				-1.0
-			} else {
-				1.0
-			};
-			let force = force * TIMESTEP * invert_force;
+			if barriers.blocks(
+				wrapping.0.then_some(window_dimensions.0),
+				transform_b.translation.truncate(),
+				offset,
+			) {
+				continue;
+			}
+			let force = particle_force(offset, positive_a.is_some(), positive_b.is_some())
+				* charge_product(charge_a, charge_b);
 
 			movement.add(force);
 		}
@@ -166,10 +246,14 @@ fn particles_applying_forces<M, F, F2>(
 
 fn particles_cancelling(
 	window_dimensions: Res<WindowDimensions>,
-	mut positive_particles: Query<(&mut Cancelled, &Transform), (With<Particle>, With<Positive>)>,
+	mut totals: ResMut<EventTotals>,
+	mut positive_particles: Query<
+		(&mut Cancelled, &Transform),
+		(With<Particle>, With<Positive>, Without<Inert>),
+	>,
 	mut negative_particles: Query<
 		(&mut Cancelled, &Transform),
-		(With<Particle>, Without<Positive>),
+		(With<Particle>, Without<Positive>, Without<Inert>),
 	>,
 ) {
 	for (mut cancelled_pos, transform_pos) in &mut positive_particles {
@@ -183,6 +267,7 @@ fn particles_cancelling(
 				if offset.length_squared() < PARTICLE_CANCEL_DISTANCE.powi(2) {
 					cancelled_pos.0 = true;
 					cancelled_neg.0 = true;
+					totals.cancellations += 2;
 				}
 			}
 		}
@@ -197,9 +282,28 @@ fn despawn_cancelled_particles(mut commands: Commands, particles: Query<(Entity,
 	}
 }
 
+/// Despawns particles that reached their [`Lifetime`], or the age at which decay despawns them.
+pub fn age_particles(
+	mut commands: Commands,
+	decay: Res<Decay>,
+	mut totals: ResMut<EventTotals>,
+	mut particles: Query<(Entity, &mut Age, Option<&Lifetime>), With<Particle>>,
+) {
+	let decay_lifetime = decay.lifetime();
+	for (entity, mut age, lifetime) in &mut particles {
+		age.0 += decay.rate * TIMESTEP;
+		if lifetime.is_some_and(|lifetime| age.0 >= lifetime.0)
+			|| decay_lifetime.is_some_and(|lifetime| age.0 >= lifetime)
+		{
+			commands.entity(entity).despawn();
+			totals.expired += 1;
+		}
+	}
+}
+
 fn spawn_initial_particles(
 	mut commands: Commands,
-	mut next_batch: ResMut<NextBatch>,
+	mut spawner: ParticleSpawner,
 	window_dimensions: Res<WindowDimensions>,
 ) {
 	let middle = window_dimensions.0 / 2.0;
@@ -210,47 +314,47 @@ fn spawn_initial_particles(
 		smallest_dimension * 0.9 / 2.0,
 		INITIAL_PARTICLE_COUNT,
 	) {
-		spawn_particle_at_location(&mut commands, &mut next_batch, point, true);
+		spawn_particle_at_location(&mut commands, &mut spawner, point, true);
 	}
 }
 
 pub fn spawn_particle_at_location(
 	commands: &mut Commands,
-	next_batch: &mut ResMut<NextBatch>,
+	spawner: &mut ParticleSpawner,
 	position: Vec2,
 	positive: bool,
-) {
-	let draw_properties = if positive {
-		draw_properties::POSITIVE_PARTICLE
-	} else {
-		draw_properties::NEGATIVE_PARTICLE
-	};
+) -> Entity {
 	let DrawProperties {
 		draw_priority,
 		size,
 		..
-	} = draw_properties;
+	} = draw_properties::particle(positive);
 
 	let mut entity_commands = commands.spawn((
 		Particle,
 		Movement::default(),
+		Velocity::default(),
 		Cancelled::default(),
+		Age::default(),
+		ParticleId(spawner.next_id.0),
 		Transform {
 			translation: position.extend(draw_priority),
 			scale: (Vec2::ONE * size).extend(1.0),
 			..default()
 		},
 	));
+	spawner.next_id.0 += 1;
 	if positive {
 		entity_commands.insert(Positive);
 	}
-	if next_batch.0 == 1 {
+	if spawner.next_batch.0 == 1 {
 		entity_commands.insert(BatchTwo);
 		entity_commands.insert(MovementBatch2::default());
-		next_batch.0 = 0;
+		spawner.next_batch.0 = 0;
 	} else {
-		next_batch.0 += 1;
+		spawner.next_batch.0 += 1;
 	}
+	entity_commands.id()
 }
 
 fn give_particles_sprites(
@@ -259,11 +363,7 @@ fn give_particles_sprites(
 	particles: Query<(Entity, Has<Positive>), (With<Particle>, Without<Sprite>)>,
 ) {
 	for (entity, positive) in particles {
-		let draw_properties = if positive {
-			draw_properties::POSITIVE_PARTICLE
-		} else {
-			draw_properties::NEGATIVE_PARTICLE
-		};
+		let draw_properties = draw_properties::particle(positive);
 		let image = draw_properties
 			.texture
 			.and_then(|texture| texture_map.0.get(&texture))
@@ -281,4 +381,11 @@ fn give_particles_sprites(
 pub struct BatchTwo;
 
 #[derive(Resource, Default)]
-pub struct NextBatch(u8);
+struct NextBatch(u8);
+
+/// What [`spawn_particle_at_location`] needs to keep track of between particles.
+#[derive(SystemParam)]
+pub struct ParticleSpawner<'w> {
+	next_batch: ResMut<'w, NextBatch>,
+	next_id: ResMut<'w, NextParticleId>,
+}
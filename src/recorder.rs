@@ -0,0 +1,227 @@
+use std::{
+	fs::File,
+	io::{BufWriter, Write},
+	path::{Path, PathBuf},
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+	TIMESTEP, WindowDimensions,
+	analysis::{density_variation, nearest_neighbour_summary},
+	cli::CommandLine,
+	common::Positive,
+	input::Action,
+	particle::Particle,
+	stats::EventTotals,
+};
+
+pub struct RecorderPlugin;
+
+impl Plugin for RecorderPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<MetricsRecorder>()
+			.add_systems(Startup, start_recording_from_command_line)
+			.add_systems(Update, toggle_recording)
+			.add_systems(FixedPostUpdate, record_metrics);
+	}
+}
+
+/// How many ticks pass between recorded rows, unless given on the command line.
+const DEFAULT_INTERVAL: u32 = 10;
+/// How many cells along the shortest window dimension the recorded density variation uses.
+const DENSITY_GRID_CELLS: u32 = 8;
+
+const COLUMNS: [&str; 13] = [
+	"tick",
+	"time",
+	"positive",
+	"negative",
+	"cancelled",
+	"emitted",
+	"eaten",
+	"deleted",
+	"expired",
+	"nearest_neighbour_mean",
+	"nearest_neighbour_deviation",
+	"clark_evans",
+	"density_variation",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MetricsFormat {
+	Csv,
+	JsonLines,
+}
+
+impl MetricsFormat {
+	fn from_path(path: &Path) -> Self {
+		match path.extension().and_then(|extension| extension.to_str()) {
+			Some("json" | "jsonl") => Self::JsonLines,
+			_ => Self::Csv,
+		}
+	}
+}
+
+struct MetricsFile {
+	path: PathBuf,
+	format: MetricsFormat,
+	writer: BufWriter<File>,
+}
+
+/// Writes a row of metrics to a file every few ticks, while recording. Event counts in a row are those since the previous row.
+#[derive(Resource)]
+pub struct MetricsRecorder {
+	file: Option<MetricsFile>,
+	interval: u32,
+	tick: u64,
+	previous_totals: EventTotals,
+}
+
+impl Default for MetricsRecorder {
+	fn default() -> Self {
+		Self {
+			file: None,
+			interval: DEFAULT_INTERVAL,
+			tick: 0,
+			previous_totals: EventTotals::default(),
+		}
+	}
+}
+
+impl MetricsRecorder {
+	fn start(&mut self, path: PathBuf, totals: EventTotals) {
+		let format = MetricsFormat::from_path(&path);
+		let file = match File::create(&path) {
+			Ok(file) => file,
+			Err(error) => {
+				error!("Could not create {}: {error}", path.display());
+				return;
+			}
+		};
+		let mut writer = BufWriter::new(file);
+		if format == MetricsFormat::Csv
+			&& let Err(error) = writeln!(writer, "{}", COLUMNS.join(","))
+		{
+			error!("Could not write to {}: {error}", path.display());
+			return;
+		}
+		info!("Recording metrics to {}", path.display());
+		self.previous_totals = totals;
+		self.file = Some(MetricsFile {
+			path,
+			format,
+			writer,
+		});
+	}
+	fn stop(&mut self) {
+		if let Some(mut file) = self.file.take() {
+			match file.writer.flush() {
+				Ok(()) => info!("Stopped recording metrics to {}", file.path.display()),
+				Err(error) => error!("Could not write to {}: {error}", file.path.display()),
+			}
+		}
+	}
+	pub fn is_recording(&self) -> bool {
+		self.file.is_some()
+	}
+}
+
+fn start_recording_from_command_line(
+	command_line: Res<CommandLine>,
+	totals: Res<EventTotals>,
+	mut recorder: ResMut<MetricsRecorder>,
+) {
+	if let Some(interval) = command_line.metrics_interval {
+		recorder.interval = interval;
+	}
+	if let Some(path) = &command_line.metrics_path {
+		recorder.start(path.clone(), *totals);
+	}
+}
+
+fn toggle_recording(
+	action_state: Query<&ActionState<Action>>,
+	totals: Res<EventTotals>,
+	mut recorder: ResMut<MetricsRecorder>,
+) {
+	if !action_state
+		.single()
+		.unwrap()
+		.just_pressed(&Action::ToggleMetricsRecording)
+	{
+		return;
+	}
+	if recorder.is_recording() {
+		recorder.stop();
+	} else {
+		let timestamp = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map_or(0, |duration| duration.as_secs());
+		recorder.start(PathBuf::from(format!("metrics-{timestamp}.csv")), *totals);
+	}
+}
+
+fn record_metrics(
+	window_dimensions: Res<WindowDimensions>,
+	totals: Res<EventTotals>,
+	mut recorder: ResMut<MetricsRecorder>,
+	particles: Query<(Has<Positive>, &Transform), With<Particle>>,
+) {
+	recorder.tick += 1;
+	let tick = recorder.tick;
+	if recorder.file.is_none() || !tick.is_multiple_of(recorder.interval as u64) {
+		return;
+	}
+
+	let mut positive_count = 0u32;
+	let mut positions = Vec::with_capacity(particles.iter().len());
+	for (positive, transform) in &particles {
+		if positive {
+			positive_count += 1;
+		}
+		positions.push(transform.translation.truncate());
+	}
+	let nearest_neighbours = nearest_neighbour_summary(&positions, window_dimensions.get());
+	let previous = recorder.previous_totals;
+	let values = [
+		tick.to_string(),
+		(tick as f32 * TIMESTEP).to_string(),
+		positive_count.to_string(),
+		(positions.len() as u32 - positive_count).to_string(),
+		(totals.cancellations - previous.cancellations).to_string(),
+		(totals.emitted - previous.emitted).to_string(),
+		(totals.eaten - previous.eaten).to_string(),
+		(totals.deleted - previous.deleted).to_string(),
+		(totals.expired - previous.expired).to_string(),
+		optional_value(nearest_neighbours.map(|summary| summary.mean)),
+		optional_value(nearest_neighbours.map(|summary| summary.standard_deviation)),
+		optional_value(nearest_neighbours.map(|summary| summary.clark_evans)),
+		density_variation(&positions, window_dimensions.get(), DENSITY_GRID_CELLS).to_string(),
+	];
+	recorder.previous_totals = *totals;
+
+	let file = recorder.file.as_mut().unwrap();
+	let result = match file.format {
+		MetricsFormat::Csv => writeln!(file.writer, "{}", values.join(",")),
+		MetricsFormat::JsonLines => {
+			let fields: Vec<_> = COLUMNS
+				.iter()
+				.zip(&values)
+				.map(|(column, value)| format!("\"{column}\":{value}"))
+				.collect();
+			writeln!(file.writer, "{{{}}}", fields.join(","))
+		}
+	};
+	if let Err(error) = result {
+		error!("Could not write to {}: {error}", file.path.display());
+		recorder.stop();
+	}
+}
+
+/// Missing values are written as `null`, which suits JSON, and which most CSV readers can be told to treat as missing.
+fn optional_value(value: Option<f32>) -> String {
+	value.map_or_else(|| String::from("null"), |value| value.to_string())
+}
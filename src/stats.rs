@@ -0,0 +1,203 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{
+	TIMESTEP, WindowDimensions,
+	analysis::{
+		NearestNeighbourSummary, density_variation, nearest_neighbour_summary, radial_distribution,
+	},
+	common::Positive,
+	movement::Velocity,
+	particle::Particle,
+};
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<EventTotals>()
+			.init_resource::<StatsHistory>()
+			.add_systems(FixedPostUpdate, sample_statistics);
+	}
+}
+
+/// How often a sample is added to the history, in seconds.
+pub const SAMPLE_INTERVAL: f32 = 0.25;
+/// How many seconds of samples the history keeps.
+pub const HISTORY_DURATION: f32 = 30.0;
+/// How many cells along the shortest window dimension the density uniformity metric divides the window into.
+const DENSITY_GRID_CELLS: u32 = 8;
+/// The distance up to which the radial distribution function is sampled.
+pub const RADIAL_DISTRIBUTION_RADIUS: f32 = 100.0;
+const RADIAL_DISTRIBUTION_BINS: usize = 50;
+
+/// Running totals of events since the application launched. Systems causing these events increment them; anything interested in rates keeps its own previous totals to compare against.
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct EventTotals {
+	/// Particles removed by meeting a particle of opposing polarity.
+	pub cancellations: u64,
+	/// Particles spawned by emitters.
+	pub emitted: u64,
+	/// Particles eaten by eaters.
+	pub eaten: u64,
+	/// Particles removed by deleters and slow deleters.
+	pub deleted: u64,
+	/// Particles removed by reaching the end of their lifetime, or by decaying.
+	pub expired: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Statistic {
+	PositiveCount,
+	NegativeCount,
+	CancellationRate,
+	EmissionRate,
+	EatingRate,
+	DeletionRate,
+	MeanSpeed,
+	DensityVariation,
+	SpacingRatio,
+}
+
+impl Statistic {
+	pub const ALL: [Self; 9] = [
+		Self::PositiveCount,
+		Self::NegativeCount,
+		Self::CancellationRate,
+		Self::EmissionRate,
+		Self::EatingRate,
+		Self::DeletionRate,
+		Self::MeanSpeed,
+		Self::DensityVariation,
+		Self::SpacingRatio,
+	];
+
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::PositiveCount => "Positive",
+			Self::NegativeCount => "Negative",
+			Self::CancellationRate => "Cancelled/s",
+			Self::EmissionRate => "Emitted/s",
+			Self::EatingRate => "Eaten/s",
+			Self::DeletionRate => "Deleted/s",
+			Self::MeanSpeed => "Mean speed",
+			Self::DensityVariation => "Density CV",
+			Self::SpacingRatio => "Clark-Evans",
+		}
+	}
+}
+
+/// The most recent samples of every [`Statistic`], oldest first, and the latest snapshot of the spatial distribution.
+#[derive(Resource)]
+pub struct StatsHistory {
+	samples: [VecDeque<f32>; Statistic::ALL.len()],
+	nearest_neighbours: Option<NearestNeighbourSummary>,
+	radial_distribution: Vec<f32>,
+	previous_totals: EventTotals,
+	time_since_sample: f32,
+}
+
+impl StatsHistory {
+	/// The maximum number of samples kept per statistic.
+	pub const CAPACITY: usize = (HISTORY_DURATION / SAMPLE_INTERVAL) as usize;
+
+	pub fn samples(&self, statistic: Statistic) -> &VecDeque<f32> {
+		&self.samples[statistic as usize]
+	}
+	pub fn latest(&self, statistic: Statistic) -> Option<f32> {
+		self.samples(statistic).back().copied()
+	}
+	pub fn nearest_neighbours(&self) -> Option<NearestNeighbourSummary> {
+		self.nearest_neighbours
+	}
+	pub fn radial_distribution(&self) -> &[f32] {
+		&self.radial_distribution
+	}
+	fn push(&mut self, statistic: Statistic, value: f32) {
+		let samples = &mut self.samples[statistic as usize];
+		if samples.len() >= Self::CAPACITY {
+			samples.pop_front();
+		}
+		samples.push_back(value);
+	}
+}
+
+impl Default for StatsHistory {
+	fn default() -> Self {
+		Self {
+			samples: std::array::from_fn(|_| VecDeque::with_capacity(Self::CAPACITY)),
+			nearest_neighbours: None,
+			radial_distribution: Vec::new(),
+			previous_totals: EventTotals::default(),
+			time_since_sample: 0.0,
+		}
+	}
+}
+
+fn sample_statistics(
+	window_dimensions: Res<WindowDimensions>,
+	totals: Res<EventTotals>,
+	mut history: ResMut<StatsHistory>,
+	particles: Query<(Has<Positive>, &Velocity, &Transform), With<Particle>>,
+) {
+	history.time_since_sample += TIMESTEP;
+	if history.time_since_sample < SAMPLE_INTERVAL {
+		return;
+	}
+	let elapsed = history.time_since_sample;
+	history.time_since_sample = 0.0;
+
+	let mut positive_count = 0;
+	let mut speed_sum = 0.0;
+	let mut positions = Vec::with_capacity(particles.iter().len());
+	for (positive, velocity, transform) in &particles {
+		if positive {
+			positive_count += 1;
+		}
+		speed_sum += velocity.get().length();
+		positions.push(transform.translation.truncate());
+	}
+	let count = positions.len();
+	let mean_speed = if count == 0 {
+		0.0
+	} else {
+		speed_sum / count as f32
+	};
+
+	let previous = history.previous_totals;
+	let rate = |current: u64, previous: u64| (current - previous) as f32 / elapsed;
+	history.push(Statistic::PositiveCount, positive_count as f32);
+	history.push(Statistic::NegativeCount, (count - positive_count) as f32);
+	history.push(
+		Statistic::CancellationRate,
+		rate(totals.cancellations, previous.cancellations),
+	);
+	history.push(
+		Statistic::EmissionRate,
+		rate(totals.emitted, previous.emitted),
+	);
+	history.push(Statistic::EatingRate, rate(totals.eaten, previous.eaten));
+	history.push(
+		Statistic::DeletionRate,
+		rate(totals.deleted, previous.deleted),
+	);
+	history.push(Statistic::MeanSpeed, mean_speed);
+	history.push(
+		Statistic::DensityVariation,
+		density_variation(&positions, window_dimensions.get(), DENSITY_GRID_CELLS),
+	);
+	let nearest_neighbours = nearest_neighbour_summary(&positions, window_dimensions.get());
+	history.push(
+		Statistic::SpacingRatio,
+		nearest_neighbours.map_or(0.0, |summary| summary.clark_evans),
+	);
+	history.nearest_neighbours = nearest_neighbours;
+	history.radial_distribution = radial_distribution(
+		&positions,
+		window_dimensions.get(),
+		RADIAL_DISTRIBUTION_RADIUS,
+		RADIAL_DISTRIBUTION_BINS,
+	);
+	history.previous_totals = *totals;
+}
@@ -0,0 +1,121 @@
+use std::{
+	fs::File,
+	io::{BufWriter, Write},
+	path::PathBuf,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+	cli::CommandLine,
+	common::Positive,
+	input::Action,
+	movement::Velocity,
+	particle::{Particle, ParticleId},
+};
+
+pub struct TrajectoryPlugin;
+
+impl Plugin for TrajectoryPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<TrajectoryRecorder>()
+			.add_systems(Startup, start_recording_from_command_line)
+			.add_systems(Update, toggle_recording)
+			.add_systems(FixedPostUpdate, record_trajectories);
+	}
+}
+
+const HEADER: &str = "tick,id,positive,x,y,velocity_x,velocity_y";
+
+/// Writes the state of every particle to a CSV file every tick, while recording. A particle appears from the tick it spawns in until the last tick before it is despawned, under a [`ParticleId`] that is never reused.
+#[derive(Resource, Default)]
+pub struct TrajectoryRecorder {
+	file: Option<(PathBuf, BufWriter<File>)>,
+	tick: u64,
+}
+
+impl TrajectoryRecorder {
+	fn start(&mut self, path: PathBuf) {
+		let file = match File::create(&path) {
+			Ok(file) => file,
+			Err(error) => {
+				error!("Could not create {}: {error}", path.display());
+				return;
+			}
+		};
+		let mut writer = BufWriter::new(file);
+		if let Err(error) = writeln!(writer, "{HEADER}") {
+			error!("Could not write to {}: {error}", path.display());
+			return;
+		}
+		info!("Recording trajectories to {}", path.display());
+		self.file = Some((path, writer));
+	}
+	fn stop(&mut self) {
+		if let Some((path, mut writer)) = self.file.take() {
+			match writer.flush() {
+				Ok(()) => info!("Stopped recording trajectories to {}", path.display()),
+				Err(error) => error!("Could not write to {}: {error}", path.display()),
+			}
+		}
+	}
+}
+
+fn start_recording_from_command_line(
+	command_line: Res<CommandLine>,
+	mut recorder: ResMut<TrajectoryRecorder>,
+) {
+	if let Some(path) = &command_line.trajectories_path {
+		recorder.start(path.clone());
+	}
+}
+
+fn toggle_recording(
+	action_state: Query<&ActionState<Action>>,
+	mut recorder: ResMut<TrajectoryRecorder>,
+) {
+	if !action_state
+		.single()
+		.unwrap()
+		.just_pressed(&Action::ToggleTrajectoryRecording)
+	{
+		return;
+	}
+	if recorder.file.is_some() {
+		recorder.stop();
+	} else {
+		let timestamp = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map_or(0, |duration| duration.as_secs());
+		recorder.start(PathBuf::from(format!("trajectories-{timestamp}.csv")));
+	}
+}
+
+fn record_trajectories(
+	mut recorder: ResMut<TrajectoryRecorder>,
+	particles: Query<(&ParticleId, Has<Positive>, &Transform, &Velocity), With<Particle>>,
+) {
+	recorder.tick += 1;
+	let tick = recorder.tick;
+	let Some((path, writer)) = &mut recorder.file else {
+		return;
+	};
+
+	let result = particles
+		.iter()
+		.try_for_each(|(id, positive, transform, velocity)| {
+			let position = transform.translation;
+			let velocity = velocity.get();
+			writeln!(
+				writer,
+				"{tick},{},{},{},{},{},{}",
+				id.0, positive as u8, position.x, position.y, velocity.x, velocity.y
+			)
+		});
+	if let Err(error) = result {
+		error!("Could not write to {}: {error}", path.display());
+		recorder.stop();
+	}
+}
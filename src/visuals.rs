@@ -0,0 +1,193 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+	WindowDimensions,
+	common::Positive,
+	decay::{Charge, Inert},
+	draw_properties,
+	input::Action,
+	movement::{MAX_SPEED, Velocity, apply_movement},
+	particle::{Age, Lifetime, Particle},
+};
+
+pub struct VisualsPlugin;
+
+impl Plugin for VisualsPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<Trails>()
+			.init_resource::<ColorMode>()
+			.add_systems(
+				Update,
+				(
+					(toggle_trails, draw_trails).chain(),
+					(cycle_color_mode, color_particles).chain(),
+				),
+			)
+			.add_systems(FixedUpdate, record_trails.after(apply_movement));
+	}
+}
+
+/// How many ticks of positions a trail remembers.
+const TRAIL_LENGTH: usize = 20;
+/// The opacity of the newest part of a trail. Older parts fade out linearly from there.
+const TRAIL_ALPHA: f32 = 0.5;
+/// How many seconds before the end of its lifetime a particle starts fading out.
+const LIFETIME_FADE: f32 = 1.0;
+/// How opaque inert particles are drawn. Particles losing their charge fade towards this.
+const INERT_ALPHA: f32 = 0.3;
+/// The brightness of a stationary particle in speed colouring mode, relative to its normal colour.
+const MIN_SPEED_BRIGHTNESS: f32 = 0.25;
+
+#[derive(Resource, Default)]
+pub struct Trails(bool);
+
+/// What the colour of a particle represents.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+	#[default]
+	Polarity,
+	/// Brighter means faster.
+	Speed,
+	/// Hue represents the direction of movement. Positive particles are lighter.
+	Direction,
+}
+
+impl ColorMode {
+	fn next(self) -> Self {
+		match self {
+			Self::Polarity => Self::Speed,
+			Self::Speed => Self::Direction,
+			Self::Direction => Self::Polarity,
+		}
+	}
+}
+
+/// Past positions of a particle, oldest first.
+#[derive(Component, Default)]
+pub struct Trail(VecDeque<Vec2>);
+
+fn toggle_trails(
+	mut commands: Commands,
+	mut trails: ResMut<Trails>,
+	action_state: Query<&ActionState<Action>>,
+	particles: Query<Entity, With<Trail>>,
+) {
+	if action_state
+		.single()
+		.unwrap()
+		.just_pressed(&Action::ToggleTrails)
+	{
+		trails.0 = !trails.0;
+		if !trails.0 {
+			for particle in &particles {
+				commands.entity(particle).remove::<Trail>();
+			}
+		}
+	}
+}
+
+fn record_trails(
+	mut commands: Commands,
+	trails: Res<Trails>,
+	mut particles: Query<(Entity, &Transform, Option<&mut Trail>), With<Particle>>,
+) {
+	if !trails.0 {
+		return;
+	}
+	for (entity, transform, trail) in &mut particles {
+		let position = transform.translation.truncate();
+		if let Some(mut trail) = trail {
+			if trail.0.len() >= TRAIL_LENGTH {
+				trail.0.pop_front();
+			}
+			trail.0.push_back(position);
+		} else {
+			// It may have been despawned this tick.
+			commands
+				.entity(entity)
+				.try_insert(Trail(VecDeque::from([position])));
+		}
+	}
+}
+
+fn draw_trails(
+	mut gizmos: Gizmos,
+	window_dimensions: Res<WindowDimensions>,
+	particles: Query<(&Trail, &Sprite), With<Particle>>,
+) {
+	let half_dimensions = window_dimensions.get() / 2.0;
+	for (trail, sprite) in &particles {
+		let count = trail.0.len();
+		for (index, (start, end)) in trail.0.iter().zip(trail.0.iter().skip(1)).enumerate() {
+			let jump = (*end - *start).abs();
+			// A jump of more than half the screen means the particle wrapped around an edge.
+			if jump.x > half_dimensions.x || jump.y > half_dimensions.y {
+				continue;
+			}
+			let alpha = |index: usize| TRAIL_ALPHA * (index + 1) as f32 / count as f32;
+			gizmos.line_gradient_2d(
+				*start,
+				*end,
+				sprite.color.with_alpha(alpha(index)),
+				sprite.color.with_alpha(alpha(index + 1)),
+			);
+		}
+	}
+}
+
+fn cycle_color_mode(mut color_mode: ResMut<ColorMode>, action_state: Query<&ActionState<Action>>) {
+	if action_state
+		.single()
+		.unwrap()
+		.just_pressed(&Action::CycleColorMode)
+	{
+		*color_mode = color_mode.next();
+	}
+}
+
+fn color_particles(
+	color_mode: Res<ColorMode>,
+	mut particles: Query<
+		(
+			&mut Sprite,
+			Has<Positive>,
+			&Velocity,
+			&Age,
+			Option<&Lifetime>,
+			Option<&Charge>,
+			Has<Inert>,
+		),
+		With<Particle>,
+	>,
+) {
+	for (mut sprite, positive, velocity, age, lifetime, charge, inert) in &mut particles {
+		let base_color = draw_properties::particle(positive).color;
+		sprite.color = match *color_mode {
+			ColorMode::Polarity => base_color,
+			ColorMode::Speed => {
+				let speed_fraction = (velocity.get().length() / MAX_SPEED).min(1.0);
+				Color::BLACK.mix(
+					&base_color,
+					MIN_SPEED_BRIGHTNESS + (1.0 - MIN_SPEED_BRIGHTNESS) * speed_fraction,
+				)
+			}
+			ColorMode::Direction => {
+				let hue = velocity.get().to_angle().to_degrees().rem_euclid(360.0);
+				let lightness = if positive { 0.7 } else { 0.45 };
+				Color::hsl(hue, 0.8, lightness)
+			}
+		};
+		let mut alpha = lifetime.map_or(1.0, |lifetime| {
+			((lifetime.0 - age.0) / LIFETIME_FADE).clamp(0.0, 1.0)
+		});
+		if inert {
+			alpha *= INERT_ALPHA;
+		} else if let Some(charge) = charge {
+			alpha *= INERT_ALPHA + (1.0 - INERT_ALPHA) * charge.0;
+		}
+		sprite.color.set_alpha(alpha);
+	}
+}